@@ -1,14 +1,54 @@
 use eyre::{OptionExt, Result};
 use graphql_client::Response;
 use serde::Serialize;
+use std::time::Duration;
 
+/// How often a request is retried after hitting Linear's rate limit.
+const RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Returned instead of a response when a mutation was not sent because of `--dry-run`.
+#[derive(Debug)]
+pub struct DryRun;
+
+impl std::fmt::Display for DryRun {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "dry run, mutation not sent")
+    }
+}
+
+impl std::error::Error for DryRun {}
+
+/// Returned when Linear reports that an entity, e.g. the issue a query asked for, doesn't exist.
+#[derive(Debug)]
+pub struct NotFound(pub String);
+
+impl std::fmt::Display for NotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "graphql error: {}", self.0)
+    }
+}
+
+impl std::error::Error for NotFound {}
+
+#[derive(Clone)]
 pub struct Client {
     api_key: String,
+    dry_run: bool,
 }
 
 impl Client {
     pub fn new(api_key: String) -> Self {
-        Self { api_key }
+        Self { api_key, dry_run: false }
+    }
+
+    /// Print mutations instead of sending them. Queries are still sent, so that names can be resolved and the current
+    /// state of issues can be shown. [`Client::req`] fails with [`DryRun`] for every mutation.
+    pub fn dry_run(self, dry_run: bool) -> Self {
+        Self { dry_run, ..self }
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
     }
 
     #[instrument(level = "debug", skip_all)]
@@ -20,17 +60,58 @@ impl Client {
     {
         trace!(query = %serde_json::to_string_pretty(&query).unwrap(), "sending query");
 
-        let response = reqwest::Client::new()
-            .post("https://api.linear.app/graphql")
-            .header("Authorization", &self.api_key)
-            .header("Content-Type", "application/json")
-            .json(&query)
-            .send()
-            .await?;
+        if self.dry_run {
+            let body = serde_json::to_value(&query)?;
+            let operation_name = body["operationName"].as_str().unwrap_or_default();
+            if let Some(mutation) = find_mutation(body["query"].as_str().unwrap_or_default(), operation_name) {
+                println!("# {operation_name} (not sent)");
+                println!("{}", mutation.trim());
+                println!("# variables");
+                println!("{}", serde_json::to_string_pretty(&body["variables"])?);
+                return Err(DryRun.into());
+            }
+        }
+
+        let mut attempt = 0;
+        let response_body = loop {
+            let response = reqwest::Client::new()
+                .post("https://api.linear.app/graphql")
+                .header("Authorization", &self.api_key)
+                .header("Content-Type", "application/json")
+                .json(&query)
+                .send()
+                .await?;
 
-        trace!(status = ?response.status(), headers = ?response.headers(), "response");
+            trace!(status = ?response.status(), headers = ?response.headers(), "response");
+
+            let status = response.status();
+            let reset_at = response
+                .headers()
+                .get("x-ratelimit-requests-reset")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<i64>().ok());
+            let response_body: Response<D> = response.json().await?;
 
-        let response_body: Response<D> = response.json().await?;
+            let rate_limited = status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                || response_body.errors.iter().flatten().any(|e| {
+                    e.extensions
+                        .as_ref()
+                        .and_then(|ext| ext.get("code"))
+                        .is_some_and(|code| code == "RATELIMITED")
+                });
+            if !rate_limited || attempt >= RATE_LIMIT_RETRIES {
+                break response_body;
+            }
+
+            // The reset header is a unix timestamp in milliseconds. Don't wait forever if it is far away.
+            let wait = reset_at
+                .map(|ms| ms - chrono::Utc::now().timestamp_millis())
+                .map(|ms| Duration::from_millis(ms.clamp(1_000, 60_000) as u64))
+                .unwrap_or(Duration::from_secs(2u64.pow(attempt + 1)));
+            warn!(?wait, "rate limited, retrying");
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        };
 
         match response_body.errors {
             Some(mut errors) if !errors.is_empty() => {
@@ -38,6 +119,9 @@ impl Client {
                     error!("graphql error {}", serde_json::to_string_pretty(&error).unwrap());
                 }
                 let error = errors.pop().unwrap();
+                if error.message.starts_with("Entity not found") {
+                    return Err(NotFound(error.message).into());
+                }
                 return Err(eyre::eyre!("graphql error: {}", error.message));
             }
             _ => (),
@@ -45,4 +129,49 @@ impl Client {
 
         response_body.data.ok_or_eyre("no data")
     }
+
+    /// Upload a file to a signed url as returned by the `fileUpload` mutation. The url carries its own authorization,
+    /// so the API key is not sent along.
+    #[instrument(level = "debug", skip_all, fields(%url))]
+    pub async fn upload(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        content_type: &str,
+        body: Vec<u8>,
+    ) -> Result<()> {
+        let mut request = reqwest::Client::new()
+            .put(url)
+            .header("Content-Type", content_type)
+            .header("Cache-Control", "public, max-age=31536000");
+        for (key, value) in headers {
+            request = request.header(key, value);
+        }
+
+        let response = request.body(body).send().await?;
+        trace!(status = ?response.status(), headers = ?response.headers(), "response");
+        response.error_for_status()?;
+
+        Ok(())
+    }
+}
+
+/// Find the definition of the mutation `operation_name` in a document. A document can contain several operations, only
+/// the one that is executed is returned. Returns `None` if the operation is a query.
+fn find_mutation<'a>(document: &'a str, operation_name: &str) -> Option<&'a str> {
+    let header = format!("mutation {operation_name}");
+    let start = document.match_indices(&header).map(|(i, _)| i).find(|&i| {
+        document[i + header.len()..]
+            .chars()
+            .next()
+            .is_some_and(|c| c == '(' || c == '{' || c.is_whitespace())
+    })?;
+
+    let rest = &document[start..];
+    let end = ["\nquery ", "\nmutation ", "\nfragment "]
+        .iter()
+        .filter_map(|next| rest[1..].find(next).map(|i| i + 1))
+        .min()
+        .unwrap_or(rest.len());
+    Some(&rest[..end])
 }
@@ -0,0 +1,190 @@
+//! Rendering of timestamps. The format and the timezone are chosen once (from the command line or the config file)
+//! and then used by all commands that print dates.
+
+use chrono::{
+    format::{
+        Item,
+        StrftimeItems,
+    },
+    FixedOffset,
+    Local,
+    NaiveDate,
+    SecondsFormat,
+    TimeZone,
+    Utc,
+};
+use std::{
+    str::FromStr,
+    sync::OnceLock,
+};
+
+pub type DateTime = chrono::DateTime<Utc>;
+
+const DEFAULT_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+#[derive(Clone, Debug, Default)]
+pub enum DateFormat {
+    /// `2024-09-01 14:30`
+    #[default]
+    Default,
+    /// `3h ago`, `in 2d`
+    Relative,
+    /// `2024-09-01T14:30:00+02:00`
+    Iso,
+    /// Any strftime format string, e.g. `%d.%m.%Y %H:%M`.
+    Custom(String),
+}
+
+impl FromStr for DateFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(Self::Default),
+            "relative" => Ok(Self::Relative),
+            "iso" | "iso8601" | "rfc3339" => Ok(Self::Iso),
+            // Formatting with an invalid specifier panics, so it is rejected here rather than when printing.
+            s if s.contains('%') => {
+                if StrftimeItems::new(s).any(|item| item == Item::Error) {
+                    return Err(format!("invalid date format {s:?}, it contains an unknown strftime specifier"));
+                }
+                Ok(Self::Custom(s.to_string()))
+            }
+            s => Err(format!(
+                "invalid date format {s:?}, expected 'default', 'relative', 'iso' or a strftime format string"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum Timezone {
+    #[default]
+    Local,
+    Named(chrono_tz::Tz),
+    Fixed(FixedOffset),
+}
+
+impl FromStr for Timezone {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("local") {
+            return Ok(Self::Local);
+        }
+        if let Ok(offset) = s.parse::<FixedOffset>() {
+            return Ok(Self::Fixed(offset));
+        }
+        s.parse::<chrono_tz::Tz>()
+            .map(Self::Named)
+            .map_err(|_| format!("unknown timezone {s:?}, expected 'local', an IANA name or an offset like '+02:00'"))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct Settings {
+    format: DateFormat,
+    timezone: Timezone,
+}
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+pub fn init(format: DateFormat, timezone: Timezone) {
+    let _ = SETTINGS.set(Settings { format, timezone });
+}
+
+fn settings() -> &'static Settings {
+    SETTINGS.get_or_init(Settings::default)
+}
+
+/// Format a timestamp according to the configured format and timezone.
+pub fn fmt(date: DateTime) -> String {
+    let Settings { format, timezone } = settings();
+    match timezone {
+        Timezone::Local => fmt_in(date.with_timezone(&Local), format),
+        Timezone::Named(tz) => fmt_in(date.with_timezone(tz), format),
+        Timezone::Fixed(offset) => fmt_in(date.with_timezone(offset), format),
+    }
+}
+
+fn fmt_in<Tz>(date: chrono::DateTime<Tz>, format: &DateFormat) -> String
+where
+    Tz: TimeZone,
+    Tz::Offset: std::fmt::Display,
+{
+    match format {
+        DateFormat::Default => date.format(DEFAULT_FORMAT).to_string(),
+        DateFormat::Relative => relative(date.with_timezone(&Utc)),
+        DateFormat::Iso => date.to_rfc3339_opts(SecondsFormat::Secs, true),
+        DateFormat::Custom(format) => date.format(format).to_string(),
+    }
+}
+
+/// Parse a point in time given by a human, e.g. `tomorrow 9am`, `friday 14:00`, `in 2 hours` or `2024-09-01 09:30`.
+/// Times without an offset are in the configured timezone.
+pub fn parse(s: &str) -> Result<DateTime, String> {
+    let s = s.trim();
+    let s = s.strip_prefix("in ").unwrap_or(s);
+    let err = |e: interim::DateError| format!("could not parse time {s:?}: {e}");
+
+    match &settings().timezone {
+        Timezone::Local => parse_in(s, Local::now()).map_err(err),
+        Timezone::Named(tz) => parse_in(s, Utc::now().with_timezone(tz)).map_err(err),
+        Timezone::Fixed(offset) => parse_in(s, Utc::now().with_timezone(offset)).map_err(err),
+    }
+}
+
+fn parse_in<Tz: TimeZone>(s: &str, now: chrono::DateTime<Tz>) -> Result<DateTime, interim::DateError> {
+    interim::parse_date_string(s, now, interim::Dialect::Uk).map(|date| date.with_timezone(&Utc))
+}
+
+/// Today in the configured timezone.
+pub fn today() -> NaiveDate {
+    match &settings().timezone {
+        Timezone::Local => Local::now().date_naive(),
+        Timezone::Named(tz) => Utc::now().with_timezone(tz).date_naive(),
+        Timezone::Fixed(offset) => Utc::now().with_timezone(offset).date_naive(),
+    }
+}
+
+/// Format a calendar date (e.g. a due date) which has no time or timezone attached.
+pub fn fmt_day(date: NaiveDate) -> String {
+    match &settings().format {
+        DateFormat::Relative => {
+            let days = (date - today()).num_days();
+            match days {
+                0 => "today".to_string(),
+                1 => "tomorrow".to_string(),
+                -1 => "yesterday".to_string(),
+                d if d > 0 => format!("in {}", fmt_duration(d * 86400)),
+                d => format!("{} ago", fmt_duration(-d * 86400)),
+            }
+        }
+        _ => date.format("%Y-%m-%d").to_string(),
+    }
+}
+
+fn relative(date: DateTime) -> String {
+    let seconds = (date - Utc::now()).num_seconds();
+    match seconds {
+        -59..=59 => "just now".to_string(),
+        s if s > 0 => format!("in {}", fmt_duration(s)),
+        s => format!("{} ago", fmt_duration(-s)),
+    }
+}
+
+fn fmt_duration(seconds: i64) -> String {
+    const MINUTE: i64 = 60;
+    const HOUR: i64 = 60 * MINUTE;
+    const DAY: i64 = 24 * HOUR;
+    const MONTH: i64 = 30 * DAY;
+    const YEAR: i64 = 365 * DAY;
+
+    match seconds {
+        s if s < HOUR => format!("{}m", s / MINUTE),
+        s if s < DAY => format!("{}h", s / HOUR),
+        s if s < MONTH => format!("{}d", s / DAY),
+        s if s < YEAR => format!("{}mo", s / MONTH),
+        s => format!("{}y", s / YEAR),
+    }
+}
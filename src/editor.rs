@@ -0,0 +1,83 @@
+//! Let the user write longer text in their editor, like `git commit` does. The editor is `$VISUAL` or `$EDITOR`,
+//! defaulting to `vi`.
+
+use eyre::{
+    bail,
+    Context as _,
+    Result,
+};
+use std::{
+    io::Write as _,
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::Command,
+};
+
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Open `text` in the editor and return what the user saved. `name` is used for the temporary file, editors pick the
+/// syntax highlighting from its extension.
+///
+/// The file is created in a new directory only the user can access, issue descriptions and comments can be private.
+pub fn edit(name: &str, text: &str) -> Result<String> {
+    let editor = ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+
+    let dir = tempfile::Builder::new()
+        .prefix("lr-")
+        .tempdir()
+        .context("could not create a temporary directory")?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o700))?;
+    }
+    let file = dir.path().join(name);
+    write_private(&file, text).with_context(|| format!("could not write {}", file.display()))?;
+
+    // The editor can come with arguments (e.g. `code --wait`), so it is run by the shell with the file as `$1`.
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{editor} \"$1\""))
+        .arg(&editor)
+        .arg(&file)
+        .status();
+    let edited = std::fs::read_to_string(&file);
+    drop(dir);
+
+    let status = status.with_context(|| format!("could not run editor {editor:?}"))?;
+    if !status.success() {
+        bail!("editor {editor:?} exited with {status}");
+    }
+    edited.context("could not read the edited file")
+}
+
+/// Create a new file only the user can read and write. Fails if the file exists, instead of following a symlink.
+fn write_private(path: &Path, text: &str) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt as _;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(text.as_bytes())
+}
+
+/// Save `text` to a file that is kept after `lr` exits, so that what the user wrote isn't lost when it can't be used.
+/// Returns the path of the file, which only the user can read.
+pub fn keep(name: &str, text: &str) -> Result<PathBuf> {
+    let mut file = tempfile::Builder::new()
+        .prefix("lr-")
+        .suffix(&format!("-{name}"))
+        .tempfile()
+        .context("could not create a file to keep the text in")?;
+    file.write_all(text.as_bytes())?;
+    let (_, path) = file.keep().context("could not keep the file")?;
+    Ok(path)
+}
@@ -0,0 +1,65 @@
+//! The few git operations `lr` needs, done by running `git` in the current directory.
+
+use eyre::{
+    bail,
+    Context as _,
+    Result,
+};
+use std::process::Command;
+
+/// Run git and return its trimmed stdout. Fails with git's stderr if it exits unsuccessfully.
+fn git(args: &[&str]) -> Result<String> {
+    debug!(?args, "git");
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .context("could not run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The name of the checked out branch, `None` if HEAD is detached.
+pub fn current_branch() -> Result<Option<String>> {
+    // Unlike `rev-parse --abbrev-ref HEAD` this also works on a branch without commits.
+    let output = Command::new("git")
+        .args(["symbolic-ref", "--short", "--quiet", "HEAD"])
+        .output()
+        .context("could not run git")?;
+    match output.status.code() {
+        Some(0) => Ok(Some(String::from_utf8_lossy(&output.stdout).trim().to_string())),
+        // `--quiet` exits with 1 and no message if HEAD is detached.
+        Some(1) => Ok(None),
+        _ => bail!("git symbolic-ref HEAD failed: {}", String::from_utf8_lossy(&output.stderr).trim()),
+    }
+}
+
+/// The directory hooks are read from, honoring `core.hooksPath`.
+pub fn hooks_dir() -> Result<std::path::PathBuf> {
+    git(&["rev-parse", "--git-path", "hooks"]).map(Into::into)
+}
+
+pub fn branch_exists(name: &str) -> Result<bool> {
+    let reference = format!("refs/heads/{name}");
+    let status = Command::new("git")
+        .args(["show-ref", "--verify", "--quiet", &reference])
+        .status()
+        .context("could not run git")?;
+    Ok(status.success())
+}
+
+/// Check out `name`, creating it from HEAD if it doesn't exist yet. Returns whether the branch was created.
+pub fn checkout(name: &str) -> Result<bool> {
+    if branch_exists(name)? {
+        git(&["checkout", name])?;
+        Ok(false)
+    } else {
+        git(&["checkout", "-b", name])?;
+        Ok(true)
+    }
+}
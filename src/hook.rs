@@ -0,0 +1,139 @@
+//! The `prepare-commit-msg` git hook, which adds a `Fixes ABC-123` trailer for the issue of the checked out branch to
+//! every commit message. It works offline, the identifier is only taken from the branch name.
+
+use crate::{
+    git,
+    identifier,
+};
+use eyre::{
+    bail,
+    Context as _,
+    Result,
+};
+use std::path::Path;
+
+const HOOK: &str = "prepare-commit-msg";
+
+const SCRIPT: &str = "#!/bin/sh
+# Installed by `lr hook install`: adds the Linear issue of the branch to commit messages.
+exec lr hook prepare-commit-msg \"$@\"
+";
+
+/// Install the hook into the current repository. Returns the path of the hook.
+pub fn install(force: bool) -> Result<std::path::PathBuf> {
+    let path = git::hooks_dir()?.join(HOOK);
+    if path.exists() && !force {
+        bail!("{} exists already, pass --force to overwrite it", path.display());
+    }
+
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    std::fs::write(&path, SCRIPT).with_context(|| format!("could not write {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(path)
+}
+
+/// Run the hook. `file` contains the commit message, `source` is what git passes as second argument, e.g. `message` for
+/// `git commit -m` or `merge` for merge commits.
+///
+/// Only branch names in the shape Linear suggests are used, other names that merely look like they contain an
+/// identifier (`feat/sha-256`) can't be told apart from real ones without asking the API.
+pub fn prepare_commit_msg(file: &Path, source: Option<&str>) -> Result<()> {
+    if matches!(source, Some("merge" | "squash")) {
+        return Ok(());
+    }
+    let Some(identifier) = git::current_branch()?
+        .as_deref()
+        .and_then(identifier::from_linear_branch)
+    else {
+        return Ok(());
+    };
+
+    let message = std::fs::read_to_string(file).context("could not read commit message")?;
+    // The comments git adds for the editor contain the branch name, and thus the identifier.
+    let mentioned = message
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .any(|l| mentions(l, &identifier));
+    if mentioned {
+        return Ok(());
+    }
+
+    std::fs::write(file, append_trailer(&message, &format!("Fixes {identifier}")))
+        .context("could not write commit message")
+}
+
+/// Whether `line` contains `identifier` as a whole word, so that `ABC-12` is not found in `ABC-123`.
+fn mentions(line: &str, identifier: &str) -> bool {
+    let line = line.to_ascii_uppercase();
+    line.match_indices(identifier).any(|(start, _)| {
+        let before = line[..start].chars().next_back();
+        let after = line[start + identifier.len()..].chars().next();
+        !before.is_some_and(|c| c.is_alphanumeric()) && !after.is_some_and(|c| c.is_alphanumeric())
+    })
+}
+
+/// Insert `trailer` after the message, but in front of the comments git adds for the editor.
+fn append_trailer(message: &str, trailer: &str) -> String {
+    let lines = message.lines().collect::<Vec<_>>();
+    let end = lines.iter().position(|l| l.starts_with('#')).unwrap_or(lines.len());
+    let (content, comments) = lines.split_at(end);
+    let content = content.join("\n");
+
+    let mut result = format!("{}\n\n{trailer}\n", content.trim_end());
+    if !comments.is_empty() {
+        result.push('\n');
+        result.push_str(&comments.join("\n"));
+        result.push('\n');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trailer_after_message() {
+        assert_eq!(
+            append_trailer("Fix login\n", "Fixes ENG-1"),
+            "Fix login\n\nFixes ENG-1\n"
+        );
+        assert_eq!(
+            append_trailer("Fix login\n\nThe body.\n\n\n", "Fixes ENG-1"),
+            "Fix login\n\nThe body.\n\nFixes ENG-1\n"
+        );
+    }
+
+    #[test]
+    fn trailer_before_comments() {
+        let message = "Fix login\n# Please enter the commit message\n# On branch robert/eng-1-login\n";
+        assert_eq!(
+            append_trailer(message, "Fixes ENG-1"),
+            "Fix login\n\nFixes ENG-1\n\n# Please enter the commit message\n# On branch robert/eng-1-login\n"
+        );
+    }
+
+    #[test]
+    fn trailer_in_empty_message() {
+        assert_eq!(append_trailer("", "Fixes ENG-1"), "\n\nFixes ENG-1\n");
+        assert_eq!(
+            append_trailer("# comment\n", "Fixes ENG-1"),
+            "\n\nFixes ENG-1\n\n# comment\n"
+        );
+    }
+
+    #[test]
+    fn mentions_whole_words() {
+        assert!(mentions("Fixes ENG-12", "ENG-12"));
+        assert!(mentions("fix eng-12: login", "ENG-12"));
+        assert!(mentions("(ENG-12)", "ENG-12"));
+        assert!(!mentions("Fixes ENG-123", "ENG-12"));
+        assert!(!mentions("Fixes XENG-12", "ENG-12"));
+        assert!(!mentions("Fix login", "ENG-12"));
+    }
+}
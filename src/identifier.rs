@@ -0,0 +1,207 @@
+//! Finding issue identifiers in the places users copy them from.
+
+use std::sync::OnceLock;
+
+static DEFAULT_TEAM: OnceLock<Option<String>> = OnceLock::new();
+
+/// Set the key of the team that bare issue numbers belong to. Has to be called before the arguments are parsed.
+pub fn init(default_team: Option<String>) {
+    let _ = DEFAULT_TEAM.set(default_team);
+}
+
+/// Key of the team set as `default_team` in the config file.
+pub fn default_team() -> Option<&'static str> {
+    DEFAULT_TEAM.get().and_then(Option::as_deref)
+}
+
+/// Parse an issue given as `ABC-123`, `abc-123`, a url like `https://linear.app/acme/issue/ABC-123/some-title`, a
+/// UUID, a branch name or a bare number like `123`, which is combined with the default team. Returns an identifier or
+/// UUID that can be passed to the API.
+pub fn parse(input: &str) -> Result<String, String> {
+    let input = input.trim();
+
+    if let Some(identifier) = from_url(input) {
+        return Ok(identifier);
+    }
+    if input.contains("://") {
+        return Err(format!("{input:?} is not a url of a Linear issue"));
+    }
+    if is_uuid(input) {
+        return Ok(input.to_lowercase());
+    }
+    if !input.is_empty() && input.chars().all(|c| c.is_ascii_digit()) {
+        return match default_team() {
+            Some(team) => Ok(format!("{}-{input}", team.to_uppercase())),
+            None => Err(format!(
+                "issue number {input} needs a team, set `default_team` in the config file or use e.g. ABC-{input}"
+            )),
+        };
+    }
+    from_branch(input).ok_or_else(|| format!("{input:?} is not an issue identifier, url or branch name"))
+}
+
+/// Like [`parse`], but lets `-` through, which stands for identifiers read from stdin.
+pub fn parse_or_stdin(input: &str) -> Result<String, String> {
+    match input.trim() {
+        "-" => Ok("-".to_string()),
+        input => parse(input),
+    }
+}
+
+fn from_url(input: &str) -> Option<String> {
+    let url = if input.starts_with("linear.app/") {
+        reqwest::Url::parse(&format!("https://{input}")).ok()?
+    } else {
+        reqwest::Url::parse(input).ok()?
+    };
+    if !url.host_str()?.ends_with("linear.app") {
+        return None;
+    }
+
+    let mut segments = url.path_segments()?;
+    segments.find(|s| *s == "issue")?;
+    from_branch(segments.next()?)
+}
+
+pub fn is_uuid(input: &str) -> bool {
+    input.len() == 36
+        && input.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+/// Find an issue identifier like `ENG-123` in a git branch name. Linear's suggested branch names look like
+/// `robert/eng-123-fix-login`, i.e. the identifier is in lower case and followed by the title. The last path segment is
+/// searched first.
+pub fn from_branch(branch: &str) -> Option<String> {
+    branch.rsplit('/').find_map(|segment| {
+        let parts = segment.split(['-', '_']).collect::<Vec<_>>();
+        parts.windows(2).find_map(|pair| match pair {
+            [key, number] if is_team_key(key) && !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) => {
+                Some(format!("{}-{number}", key.to_uppercase()))
+            }
+            _ => None,
+        })
+    })
+}
+
+/// Prefixes of branch names that are commonly used for the kind of change rather than for a user.
+const BRANCH_TYPES: &[&str] = &[
+    "bugfix", "build", "chore", "ci", "docs", "feat", "feature", "fix", "hotfix", "perf", "refactor", "release",
+    "revert", "style", "test",
+];
+
+/// Like [`from_branch`], but only for branch names that look like the ones Linear suggests, `robert/eng-123-fix-login`,
+/// or that contain an identifier of the default team. For when the identifier can't be checked against the API, e.g.
+/// `fix/utf-8-handling` or `release/v1-2` would otherwise be taken for issues.
+pub fn from_linear_branch(branch: &str) -> Option<String> {
+    if let (Some(identifier), Some(team)) = (from_branch(branch), default_team()) {
+        if identifier
+            .rsplit_once('-')
+            .is_some_and(|(key, _)| key.eq_ignore_ascii_case(team))
+        {
+            return Some(identifier);
+        }
+    }
+
+    let (user, name) = branch.split_once('/')?;
+    if name.contains('/') || BRANCH_TYPES.contains(&user.to_ascii_lowercase().as_str()) {
+        return None;
+    }
+    let mut parts = name.splitn(3, '-');
+    let (key, number, slug) = (parts.next()?, parts.next()?, parts.next()?);
+    if !is_team_key(key) || number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) || slug.is_empty() {
+        return None;
+    }
+    Some(format!("{}-{number}", key.to_uppercase()))
+}
+
+/// Team keys start with a letter and are at most 7 letters or digits long.
+fn is_team_key(key: &str) -> bool {
+    (1..=7).contains(&key.len())
+        && key.starts_with(|c: char| c.is_ascii_alphabetic())
+        && key.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The default team can only be set once per process, so every test that depends on it sets the same one.
+    fn init_default_team() {
+        init(Some("eng".to_string()));
+    }
+
+    #[test]
+    fn parse_identifiers() {
+        assert_eq!(parse("ENG-123"), Ok("ENG-123".to_string()));
+        assert_eq!(parse(" eng-123\n"), Ok("ENG-123".to_string()));
+        assert_eq!(parse("robert/eng-123-fix-login"), Ok("ENG-123".to_string()));
+    }
+
+    #[test]
+    fn parse_urls() {
+        assert_eq!(
+            parse("https://linear.app/acme/issue/ENG-123/fix-login"),
+            Ok("ENG-123".to_string())
+        );
+        assert_eq!(parse("linear.app/acme/issue/eng-123"), Ok("ENG-123".to_string()));
+        assert!(parse("https://github.com/acme/issue/ENG-123").is_err());
+        assert!(parse("https://linear.app/acme/project/ENG-123").is_err());
+    }
+
+    #[test]
+    fn parse_uuids() {
+        let id = "3F2504E0-4F89-11D3-9A0C-0305E82C3301";
+        assert!(is_uuid(id));
+        assert_eq!(parse(id), Ok(id.to_lowercase()));
+        assert!(!is_uuid("3f2504e0-4f89-11d3-9a0c-0305e82c330"));
+        assert!(!is_uuid("3f2504e0x4f89-11d3-9a0c-0305e82c3301"));
+    }
+
+    #[test]
+    fn parse_numbers() {
+        init_default_team();
+        assert_eq!(parse("123"), Ok("ENG-123".to_string()));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse("").is_err());
+        assert!(parse("login").is_err());
+        assert!(parse("eng-").is_err());
+        assert!(parse("-").is_err());
+        assert_eq!(parse_or_stdin(" - "), Ok("-".to_string()));
+    }
+
+    #[test]
+    fn branches() {
+        assert_eq!(from_branch("robert/eng-123-fix-login"), Some("ENG-123".to_string()));
+        assert_eq!(from_branch("ENG-123"), Some("ENG-123".to_string()));
+        assert_eq!(from_branch("feature/eng_42_login"), Some("ENG-42".to_string()));
+        // The last segment is searched first.
+        assert_eq!(from_branch("eng-1/eng-2-login"), Some("ENG-2".to_string()));
+        assert_eq!(from_branch("main"), None);
+        assert_eq!(from_branch("robert/login-page"), None);
+        assert_eq!(from_branch("toolongkey-1"), None);
+        assert_eq!(from_branch("1eng-1"), None);
+    }
+
+    #[test]
+    fn linear_branches() {
+        init_default_team();
+        assert_eq!(
+            from_linear_branch("robert/abc-12-fix-login"),
+            Some("ABC-12".to_string())
+        );
+        assert_eq!(from_linear_branch("fix/eng-12"), Some("ENG-12".to_string()));
+        assert_eq!(from_linear_branch("eng-12"), Some("ENG-12".to_string()));
+        assert_eq!(from_linear_branch("fix/utf-8-handling"), None);
+        assert_eq!(from_linear_branch("release/v1-2"), None);
+        assert_eq!(from_linear_branch("feat/sha-256"), None);
+        assert_eq!(from_linear_branch("robert/abc-12"), None);
+        assert_eq!(from_linear_branch("robert/wip/abc-12-login"), None);
+        assert_eq!(from_linear_branch("abc-12-login"), None);
+    }
+}
@@ -0,0 +1,469 @@
+//! A local log of the mutations `lr` performed, so that they can be listed with `lr history` and reverted with `lr
+//! undo`. Entries are appended as JSON lines to `$XDG_DATA_HOME/linear-cli/journal.jsonl`.
+//!
+//! Reminders are not recorded, they can't be reverted. Undoing an upload removes the links to the files, the files stay
+//! in Linear's storage.
+
+use crate::{
+    client::{
+        Client,
+        DryRun,
+    },
+    dates,
+    requests::issue::{
+        archive::{
+            self,
+            Action,
+        },
+        attachment::{
+            self,
+            Attachment,
+        },
+        bulk_update,
+        comment,
+        relation,
+        subscription,
+        update,
+    },
+};
+use eyre::{
+    bail,
+    Context as _,
+    ContextCompat as _,
+    Result,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::{
+    collections::BTreeMap,
+    io::Write as _,
+    path::PathBuf,
+};
+
+pub type DateTime = chrono::DateTime<chrono::Utc>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub at: DateTime,
+    #[serde(default)]
+    pub undone: bool,
+    /// For undone updates, the time each issue was updated at by undoing it, by issue id.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub reverted_at: BTreeMap<String, DateTime>,
+    pub change: Change,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Change {
+    /// `issue update`, `issue edit` and `issue bulk-update`.
+    Update { issues: Vec<IssueChange> },
+    Create {
+        issue_id: String,
+        identifier: String,
+    },
+    Archive {
+        issue_id: String,
+        identifier: String,
+        action: Action,
+    },
+    Relate {
+        identifier: String,
+        other: String,
+        relation: relation::Stored,
+    },
+    Unrelate {
+        identifier: String,
+        other: String,
+        relations: Vec<relation::Stored>,
+    },
+    Attach {
+        identifier: String,
+        attachment: Attachment,
+    },
+    Detach {
+        identifier: String,
+        attachment: Attachment,
+    },
+    /// `issue subscribe` and `issue unsubscribe`. `user_id` is `None` for the authenticated user. `was_subscribed` is
+    /// `None` for entries written before it was recorded.
+    Subscribe {
+        identifier: String,
+        user_id: Option<String>,
+        subscribed: bool,
+        #[serde(default)]
+        was_subscribed: Option<bool>,
+    },
+    /// `issue comment`, `issue upload --as comment` and comments written in the TUI.
+    Comment {
+        identifier: String,
+        comment_id: String,
+    },
+}
+
+/// The fields of a single issue before and after an update. Only the fields that were changed are set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueChange {
+    pub id: String,
+    pub identifier: String,
+    pub before: update::Input,
+    pub after: update::Input,
+    /// The `updatedAt` of the issue right after the update, to tell whether it was changed again since.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime>,
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Update { issues } => {
+                let fields = issues.first().map(|i| changed_fields(&i.after)).unwrap_or_default();
+                match issues.as_slice() {
+                    [issue] => write!(f, "update {}: {}", issue.identifier, fields.join(", ")),
+                    _ => write!(f, "update {} issues: {}", issues.len(), fields.join(", ")),
+                }
+            }
+            Change::Create { identifier, .. } => write!(f, "create {identifier}"),
+            Change::Archive { identifier, action, .. } => write!(f, "{action} {identifier}"),
+            Change::Relate {
+                identifier,
+                other,
+                relation,
+            } => write!(f, "relate {identifier} to {other} ({})", relation.type_),
+            Change::Unrelate { identifier, other, .. } => write!(f, "unrelate {identifier} and {other}"),
+            Change::Attach { identifier, attachment } => write!(f, "attach {} to {identifier}", attachment.url),
+            Change::Detach { identifier, attachment } => write!(f, "detach {} from {identifier}", attachment.url),
+            Change::Subscribe {
+                identifier,
+                subscribed: true,
+                ..
+            } => write!(f, "subscribe to {identifier}"),
+            Change::Subscribe {
+                identifier,
+                subscribed: false,
+                ..
+            } => write!(f, "unsubscribe from {identifier}"),
+            Change::Comment { identifier, .. } => write!(f, "comment on {identifier}"),
+        }
+    }
+}
+
+fn changed_fields(input: &update::Input) -> Vec<&'static str> {
+    let update::Input {
+        title,
+        description,
+        state_id,
+        assignee_id,
+        priority,
+        label_ids,
+        cycle_id,
+        project_id,
+        estimate,
+        due_date,
+    } = input;
+    [
+        ("title", title.is_some()),
+        ("description", description.is_some()),
+        ("state", state_id.is_some()),
+        ("assignee", assignee_id.is_some()),
+        ("priority", priority.is_some()),
+        ("labels", label_ids.is_some()),
+        ("cycle", cycle_id.is_some()),
+        ("project", project_id.is_some()),
+        ("estimate", estimate.is_some()),
+        ("due date", due_date.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(name, set)| set.then_some(name))
+    .collect()
+}
+
+fn journal_file() -> Result<PathBuf> {
+    let dir = directories::ProjectDirs::from("app", "linear", "linear-cli")
+        .context("could not determine project directories")?;
+    Ok(dir.data_dir().join("journal.jsonl"))
+}
+
+/// Append a change to the journal. The mutation already happened at this point, so failing to record it is only
+/// logged.
+pub fn record(change: Change) {
+    let entry = Entry {
+        at: chrono::Utc::now(),
+        undone: false,
+        reverted_at: BTreeMap::new(),
+        change,
+    };
+    if let Err(err) = append(&entry) {
+        warn!(?err, "could not record change in journal");
+    }
+}
+
+fn append(entry: &Entry) -> Result<()> {
+    let file = journal_file()?;
+    std::fs::create_dir_all(file.parent().unwrap())?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file)
+        .context("could not open journal")?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// All entries, oldest first.
+pub fn load() -> Result<Vec<Entry>> {
+    let file = journal_file()?;
+    if !file.exists() {
+        return Ok(Vec::new());
+    }
+
+    let journal = std::fs::read_to_string(&file).context("could not read journal")?;
+    journal
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).context("could not parse journal"))
+        .collect()
+}
+
+fn save(entries: &[Entry]) -> Result<()> {
+    let file = journal_file()?;
+    let mut journal = String::new();
+    for entry in entries {
+        journal.push_str(&serde_json::to_string(entry)?);
+        journal.push('\n');
+    }
+    std::fs::write(file, journal).context("could not write journal")
+}
+
+/// Revert the last `n` changes that haven't been undone yet, newest first. Each entry is marked as undone right after
+/// it was reverted, so a failure leaves the journal consistent with Linear.
+///
+/// Updates are only reverted if the issues weren't changed again since, unless `force` is set. Otherwise edits made by
+/// others in the meantime would be overwritten.
+#[builder]
+pub async fn undo(client: &Client, n: usize, #[builder(default)] force: bool) -> Result<Vec<Change>> {
+    let mut entries = load()?;
+    let indices = entries
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, e)| !e.undone)
+        .map(|(i, _)| i)
+        .take(n)
+        .collect::<Vec<_>>();
+    if indices.is_empty() {
+        bail!("nothing to undo");
+    }
+
+    // The last time `lr` itself updated each issue, so that its own updates and reverts aren't taken for changes made
+    // by others. Otherwise undoing two updates of the same issue would fail at the second one.
+    let mut latest = BTreeMap::<String, DateTime>::new();
+    for entry in &entries {
+        for (id, at) in updated_at(entry) {
+            let latest = latest.entry(id).or_insert(at);
+            *latest = at.max(*latest);
+        }
+    }
+
+    let mut undone = Vec::new();
+    for i in indices {
+        if !force {
+            check_unchanged(client, &entries[i], &latest).await?;
+        }
+        let reverted_at = match revert(client, &entries[i].change).await {
+            // Show the mutations for all entries, not just the first one.
+            Err(err) if err.is::<DryRun>() => continue,
+            res => res.with_context(|| format!("could not undo \"{}\"", entries[i].change))?,
+        };
+        latest.extend(reverted_at.clone());
+        entries[i].undone = true;
+        entries[i].reverted_at = reverted_at;
+        save(&entries)?;
+        undone.push(entries[i].change.clone());
+    }
+    if client.is_dry_run() {
+        return Err(DryRun.into());
+    }
+
+    Ok(undone)
+}
+
+/// The times `entry` left the issues it updated at, by issue id. Entries written before `updated_at` was recorded use
+/// the time of the entry.
+fn updated_at(entry: &Entry) -> impl Iterator<Item = (String, DateTime)> + '_ {
+    let updates = match &entry.change {
+        Change::Update { issues } => issues.as_slice(),
+        _ => &[],
+    };
+    updates
+        .iter()
+        .map(|change| (change.id.clone(), change.updated_at.unwrap_or(entry.at)))
+        .chain(entry.reverted_at.iter().map(|(id, at)| (id.clone(), *at)))
+}
+
+/// Fail if an issue changed by an update was updated afterwards by someone else, i.e. after `latest`, the last time
+/// `lr` updated it.
+async fn check_unchanged(client: &Client, entry: &Entry, latest: &BTreeMap<String, DateTime>) -> Result<()> {
+    let Change::Update { issues } = &entry.change else {
+        return Ok(());
+    };
+
+    let ids = issues.iter().map(|i| i.id.clone()).collect::<Vec<_>>();
+    let current = bulk_update::lookup(client, &ids).await?;
+    let changed = issues
+        .iter()
+        .filter(|change| {
+            let updated_at = latest
+                .get(&change.id)
+                .copied()
+                .unwrap_or(change.updated_at.unwrap_or(entry.at));
+            current
+                .iter()
+                .any(|issue| issue.id == change.id && issue.updated_at > updated_at)
+        })
+        .map(|change| change.identifier.as_str())
+        .collect::<Vec<_>>();
+    if !changed.is_empty() {
+        bail!(
+            "{} changed after \"{}\", undoing it would overwrite those changes. Use --force to undo it anyway",
+            changed.join(", "),
+            entry.change
+        );
+    }
+    Ok(())
+}
+
+/// Revert `change`. Returns the time Linear updated each issue at, by issue id, if `change` was an update.
+async fn revert(client: &Client, change: &Change) -> Result<BTreeMap<String, DateTime>> {
+    let mut reverted_at = BTreeMap::new();
+    match change {
+        Change::Update { issues } => {
+            // After a bulk update many issues usually share their previous values, those are restored in batches.
+            let mut groups = Vec::<(&update::Input, Vec<String>)>::new();
+            for IssueChange { id, before, .. } in issues {
+                match groups.iter_mut().find(|(input, _)| *input == before) {
+                    Some((_, ids)) => ids.push(id.clone()),
+                    None => groups.push((before, vec![id.clone()])),
+                }
+            }
+
+            for (input, ids) in groups {
+                for chunk in ids.chunks(update::BATCH_SIZE) {
+                    match update::batch().client(client).ids(chunk.to_vec()).input(input).call().await {
+                        Err(err) if err.is::<DryRun>() => continue,
+                        res => reverted_at.extend(res?.into_iter().map(|issue| (issue.id, issue.updated_at))),
+                    };
+                }
+            }
+            if client.is_dry_run() {
+                return Err(DryRun.into());
+            }
+        }
+        // Issues can't be deleted for good by everyone, moving the issue to the trash is the closest to undoing it.
+        Change::Create { issue_id, .. } => {
+            archive::request()
+                .client(client)
+                .id(issue_id.clone())
+                .action(Action::Delete { permanently: false })
+                .call()
+                .await?;
+        }
+        Change::Archive {
+            issue_id,
+            identifier,
+            action,
+        } => {
+            let Some(inverse) = action.inverse() else {
+                bail!("{identifier} was deleted permanently");
+            };
+            archive::request()
+                .client(client)
+                .id(issue_id.clone())
+                .action(inverse)
+                .call()
+                .await?;
+        }
+        Change::Relate { relation, .. } => relation::delete_stored(client, &relation.id).await?,
+        Change::Unrelate { relations, .. } => {
+            for stored in relations {
+                relation::recreate(client, stored).await?;
+            }
+        }
+        Change::Attach { attachment, .. } => attachment::delete_by_id(client, &attachment.id).await?,
+        Change::Detach { identifier, attachment } => {
+            attachment::create()
+                .client(client)
+                .issue_id(identifier.clone())
+                .url(attachment.url.clone())
+                .title(attachment.title.clone())
+                .call()
+                .await?;
+        }
+        // The user already was (un)subscribed, there is nothing to revert.
+        Change::Subscribe {
+            subscribed,
+            was_subscribed: Some(was_subscribed),
+            ..
+        } if was_subscribed == subscribed => {}
+        Change::Subscribe {
+            identifier,
+            user_id,
+            subscribed,
+            ..
+        } => {
+            if *subscribed {
+                subscription::unsubscribe()
+                    .client(client)
+                    .issue_id(identifier.clone())
+                    .maybe_user_id(user_id.clone())
+                    .call()
+                    .await?;
+            } else {
+                subscription::subscribe()
+                    .client(client)
+                    .issue_id(identifier.clone())
+                    .maybe_user_id(user_id.clone())
+                    .call()
+                    .await?;
+            }
+        }
+        Change::Comment { comment_id, .. } => comment::delete().client(client).id(comment_id.clone()).call().await?,
+    }
+    Ok(reverted_at)
+}
+
+pub fn print(res: Result<Vec<Entry>>, n: usize, json: bool) {
+    use comfy_table::*;
+
+    let res = match res {
+        Ok(res) => res,
+        Err(err) => {
+            eprintln!("{:?}", err);
+            return;
+        }
+    };
+    let entries = res.iter().rev().take(n).collect::<Vec<_>>();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries).unwrap());
+        return;
+    }
+
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::NOTHING);
+    table.set_content_arrangement(comfy_table::ContentArrangement::DynamicFullWidth);
+    table.add_row([Cell::new("#"), Cell::new("at"), Cell::new("change"), Cell::new("")]);
+
+    for (i, entry) in entries.into_iter().enumerate() {
+        table.add_row([
+            Cell::new(i + 1),
+            Cell::new(dates::fmt(entry.at)),
+            Cell::new(&entry.change),
+            Cell::new(if entry.undone { "undone" } else { "" }),
+        ]);
+    }
+
+    println!("{table}");
+}
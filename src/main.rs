@@ -1,6 +1,16 @@
 mod client;
+mod dates;
+mod editor;
+mod git;
+mod hook;
+mod identifier;
+mod journal;
+mod pager;
+mod prompt;
 mod requests;
 mod shared;
+mod style;
+mod tui;
 
 #[macro_use]
 extern crate tracing;
@@ -8,7 +18,7 @@ extern crate tracing;
 #[macro_use]
 extern crate bon;
 
-use std::path::PathBuf;
+use std::{io::IsTerminal as _, path::PathBuf};
 
 use clap::Parser;
 use eyre::{Context as _, ContextCompat as _, Result};
@@ -20,6 +30,32 @@ struct Args {
     #[clap(long, env = "LINEAR_API_KEY")]
     api_key: Option<String>,
 
+    /// When to use colors. `auto` disables them if stdout is not a terminal or `NO_COLOR` is set.
+    #[clap(long, global = true, default_value = "auto")]
+    color: style::Choice,
+
+    /// When to render identifiers, projects and users as clickable links.
+    #[clap(long, global = true, default_value = "auto")]
+    hyperlinks: style::Choice,
+
+    /// How to render timestamps: `default`, `relative`, `iso` or a strftime format string. Overrides `date_format` in
+    /// the config file.
+    #[clap(long, global = true)]
+    date_format: Option<dates::DateFormat>,
+
+    /// Timezone used to render timestamps: `local`, an IANA name like `Europe/Berlin` or an offset like `+02:00`.
+    /// Overrides `timezone` in the config file.
+    #[clap(long, global = true)]
+    timezone: Option<dates::Timezone>,
+
+    /// Print the mutations that would be sent and what they would change, without sending them.
+    #[clap(long, global = true, action, default_value = "false")]
+    dry_run: bool,
+
+    /// Do not pipe output through `$PAGER`.
+    #[clap(long, global = true, action, default_value = "false")]
+    no_pager: bool,
+
     #[clap(subcommand)]
     cmd: Command,
 }
@@ -30,22 +66,72 @@ impl Args {
             Command::Init => false,
             Command::Me(Me { json }) => *json,
             Command::Issue {
-                cmd: IssueCommand::List(IssueList { json, .. }),
-            } => *json,
+                cmd: IssueCommand::List(IssueList { json, format, .. }),
+            } => *json || format.is_some_and(|f| f != shared::ListFormat::Table),
             Command::Issue {
                 cmd: IssueCommand::Show(IssueShow { json, .. }),
             } => *json,
             Command::Issue {
-                cmd: IssueCommand::Update(_),
+                cmd:
+                    IssueCommand::Create(_)
+                    | IssueCommand::Update(_)
+                    | IssueCommand::Edit(_)
+                    | IssueCommand::Relate(_)
+                    | IssueCommand::Unrelate(_)
+                    | IssueCommand::Attach(_)
+                    | IssueCommand::Comment(_)
+                    | IssueCommand::CommitMsg(_)
+                    | IssueCommand::PrBody(_)
+                    | IssueCommand::Detach(_)
+                    | IssueCommand::Upload(_)
+                    | IssueCommand::Archive(_)
+                    | IssueCommand::Unarchive(_)
+                    | IssueCommand::Delete(_)
+                    | IssueCommand::Restore(_)
+                    | IssueCommand::Subscribe(_)
+                    | IssueCommand::Unsubscribe(_)
+                    | IssueCommand::Remind(_)
+                    | IssueCommand::Branch(_)
+                    | IssueCommand::Start(_),
             } => false,
+            Command::Issue {
+                cmd: IssueCommand::Tree(IssueTree { json, .. }),
+            } => *json,
+            Command::Issue {
+                cmd: IssueCommand::History(IssueHistory { json, .. }),
+            } => *json,
+            Command::Issue {
+                cmd: IssueCommand::BulkUpdate(IssueBulkUpdate { json, .. }),
+            } => *json,
             Command::Team {
                 cmd: TeamCommand::List(TeamList { json, .. }),
             } => *json,
+            Command::History(History { json, .. }) => *json,
+            Command::Undo(_) => false,
+            Command::Tui(_) => false,
+            Command::Board(Board { json, .. }) => *json,
+            Command::Hook { .. } => false,
             Command::Debug { .. } => false,
         }
     }
+
+    /// Commands whose output can get long enough to be worth paging.
+    fn pager_enabled(&self) -> bool {
+        match &self.cmd {
+            Command::Issue {
+                cmd: IssueCommand::List(_) | IssueCommand::Show(_) | IssueCommand::Tree(_) | IssueCommand::History(_),
+            } => !self.no_pager,
+            Command::Team {
+                cmd: TeamCommand::List(_),
+            } => !self.no_pager,
+            Command::History(_) => !self.no_pager,
+            _ => false,
+        }
+    }
 }
 
+// Parsed once, the size of the variants does not matter.
+#[allow(clippy::large_enum_variant)]
 #[derive(Parser)]
 enum Command {
     /// Initialize the configuration of `lr`. Will prompt for the API key and
@@ -60,6 +146,14 @@ enum Command {
         #[clap(subcommand)]
         cmd: IssueCommand,
     },
+    History(History),
+    Undo(Undo),
+    Tui(Tui),
+    Board(Board),
+    Hook {
+        #[clap(subcommand)]
+        cmd: HookCommand,
+    },
 
     Debug {
         #[clap(subcommand)]
@@ -76,7 +170,35 @@ enum TeamCommand {
 enum IssueCommand {
     Show(IssueShow),
     List(IssueList),
+    Create(IssueCreate),
     Update(IssueUpdate),
+    Edit(IssueEdit),
+    BulkUpdate(IssueBulkUpdate),
+    Tree(IssueTree),
+    History(IssueHistory),
+    Relate(IssueRelate),
+    Unrelate(IssueUnrelate),
+    Attach(IssueAttach),
+    Comment(IssueComment),
+    CommitMsg(IssueCommitMsg),
+    PrBody(IssuePrBody),
+    Detach(IssueDetach),
+    Upload(IssueUpload),
+    Archive(IssueArchive),
+    Unarchive(IssueUnarchive),
+    Delete(IssueDelete),
+    Restore(IssueRestore),
+    Subscribe(IssueSubscribe),
+    Unsubscribe(IssueUnsubscribe),
+    Remind(IssueRemind),
+    Branch(IssueBranch),
+    Start(IssueStart),
+}
+
+#[derive(Parser)]
+enum HookCommand {
+    Install(HookInstall),
+    PrepareCommitMsg(HookPrepareCommitMsg),
 }
 
 #[derive(Parser)]
@@ -91,6 +213,62 @@ struct Me {
     json: bool,
 }
 
+/// List the changes `lr` made, newest first.
+#[derive(Parser)]
+struct History {
+    #[clap(short, long = "limit", default_value = "20")]
+    n: usize,
+
+    #[clap(long, action, default_value = "false")]
+    json: bool,
+}
+
+/// Revert the last changes `lr` made by restoring the previous values. Changes that were undone already are skipped.
+#[derive(Parser)]
+struct Undo {
+    /// Number of changes to revert.
+    #[clap(default_value = "1")]
+    n: usize,
+
+    /// Revert updates even if the issues were changed again since, overwriting those changes.
+    #[clap(long, action, default_value = "false")]
+    force: bool,
+}
+
+/// Browse issues in a full screen terminal UI. Select an issue to see its details, change its state, assignee or
+/// priority, comment on it, open it in the browser or copy its branch name.
+#[derive(Parser)]
+struct Tui {
+    #[clap(short, long, default_value = "updated")]
+    sort_by: shared::SortBy,
+
+    #[clap(flatten)]
+    filters: IssueFilters,
+}
+
+/// Show the issues of a team as a board, with a column per workflow state. Issues that were completed or canceled more
+/// than two weeks ago are left out unless a cycle is given.
+#[derive(Parser)]
+struct Board {
+    /// Key, name or id of the team. Defaults to `default_team` from the config file, or to picking one interactively.
+    #[clap(long)]
+    team: Option<String>,
+
+    /// Only show issues of this cycle: number, name, 'current', 'next' or 'previous'.
+    #[clap(long)]
+    cycle: Option<String>,
+
+    /// Also show workflow states without issues.
+    #[clap(long, action, default_value = "false")]
+    include_empty: bool,
+
+    #[clap(long, action, default_value = "false")]
+    json: bool,
+
+    #[clap(long, action, default_value = "false")]
+    full_width: bool,
+}
+
 /// List teams.
 #[derive(Parser)]
 struct TeamList {
@@ -101,11 +279,19 @@ struct TeamList {
     full_width: bool,
 }
 
-/// Show details about a single issue.
+/// Show details about an issue.
 #[derive(Parser)]
 struct IssueShow {
-    #[clap(help = "Linear issue identifier (e.g. 'L-1234'")]
-    id: String,
+    #[clap(
+        help = "Linear issue identifier, url or number, '-' to read identifiers from stdin. Defaults to the issue of \
+                the checked out git branch",
+        value_parser = identifier::parse_or_stdin
+    )]
+    id: Option<String>,
+
+    /// Also show all sub-issues as a tree.
+    #[clap(long, action, default_value = "false")]
+    children: bool,
 
     #[clap(long, action, default_value = "false")]
     json: bool,
@@ -114,23 +300,30 @@ struct IssueShow {
     full_width: bool,
 }
 
-/// List issues.
+/// Show an issue and all of its sub-issues as a tree.
 #[derive(Parser)]
-struct IssueList {
-    #[clap(short, long = "limit")]
-    n: Option<usize>,
-
-    #[clap(short, long, default_value = "created")]
-    sort_by: shared::SortBy,
+struct IssueTree {
+    #[clap(
+        help = "Linear issue identifier (e.g. 'L-1234'), url or number, '-' to read identifiers from stdin",
+        value_parser = identifier::parse_or_stdin
+    )]
+    id: String,
 
-    #[clap(short, long)]
-    assignee: Option<String>,
+    #[clap(long, action, default_value = "false")]
+    json: bool,
 
-    #[clap(long, value_delimiter = ',')]
-    state: Option<Vec<shared::IssueState>>,
+    #[clap(long, action, default_value = "false")]
+    full_width: bool,
+}
 
-    #[clap(long, value_delimiter = ',')]
-    not_state: Option<Vec<shared::IssueState>>,
+/// Show who changed what on an issue and when, oldest first.
+#[derive(Parser)]
+struct IssueHistory {
+    #[clap(
+        help = "Linear issue identifier (e.g. 'L-1234'), url or number, '-' to read identifiers from stdin",
+        value_parser = identifier::parse_or_stdin
+    )]
+    id: String,
 
     #[clap(long, action, default_value = "false")]
     json: bool,
@@ -139,163 +332,1636 @@ struct IssueList {
     full_width: bool,
 }
 
-/// List issues.
+/// Add a relation between two issues.
 #[derive(Parser)]
-struct IssueUpdate {
-    #[clap(help = "Linear issue identifier (e.g. 'L-1234')")]
+#[clap(group(clap::ArgGroup::new("relation").required(true).args(["blocks", "blocked_by", "duplicate_of", "related"])))]
+struct IssueRelate {
+    #[clap(
+        help = "Linear issue identifier (e.g. 'L-1234'), url or number, '-' to read identifiers from stdin",
+        value_parser = identifier::parse_or_stdin
+    )]
     id: String,
 
+    /// The issue blocks the given issue.
+    #[clap(long, value_name = "OTHER", value_parser = identifier::parse)]
+    blocks: Option<String>,
+
+    /// The issue is blocked by the given issue.
+    #[clap(long, value_name = "OTHER", value_parser = identifier::parse)]
+    blocked_by: Option<String>,
+
+    /// The issue is a duplicate of the given issue.
+    #[clap(long, value_name = "OTHER", value_parser = identifier::parse)]
+    duplicate_of: Option<String>,
+
+    /// The issue is related to the given issue.
+    #[clap(long, value_name = "OTHER", value_parser = identifier::parse)]
+    related: Option<String>,
+}
+
+/// Remove all relations between two issues.
+#[derive(Parser)]
+struct IssueUnrelate {
+    #[clap(
+        help = "Linear issue identifier (e.g. 'L-1234'), url or number, '-' to read identifiers from stdin",
+        value_parser = identifier::parse_or_stdin
+    )]
+    id: String,
+
+    #[clap(help = "Identifier, url or number of the related issue", value_parser = identifier::parse)]
+    other: String,
+}
+
+/// Attach a link to an issue. GitHub pull requests and GitLab merge requests are linked through their integrations.
+#[derive(Parser)]
+struct IssueAttach {
+    #[clap(help = "Linear issue identifier, url or number, defaults to the issue of the checked out git branch")]
+    id: Option<String>,
+
+    // Both positionals are optional for clap so that the issue can be left out, `lr issue attach URL` puts the url
+    // into `id`.
+    #[clap(value_name = "URL")]
+    url: Option<String>,
+
     #[clap(long)]
     title: Option<String>,
 
     #[clap(long)]
-    state: Option<shared::IssueState>,
+    subtitle: Option<String>,
 }
 
-#[derive(Default, Deserialize, Serialize)]
-struct Config {
-    api_key: String,
+/// Comment on an issue.
+#[derive(Parser)]
+struct IssueComment {
+    #[clap(
+        help = "Linear issue identifier, url or number, '-' to read identifiers from stdin. Defaults to the issue of \
+                the checked out git branch",
+        value_parser = identifier::parse_or_stdin
+    )]
+    id: Option<String>,
+
+    /// The comment in markdown. Read from stdin if not given, unless the identifiers are.
+    #[clap(short, long)]
+    message: Option<String>,
 }
 
-impl Config {
-    fn config_file() -> Result<PathBuf> {
-        let dir = directories::ProjectDirs::from("app", "linear", "linear-cli")
-            .context("could not determine project directories")?;
-        let config_dir = dir.config_dir();
-        let config_file = config_dir.join("config.toml");
-        Ok(config_file)
-    }
+/// Print a conventional commit message for an issue, e.g. to use with `git commit -t`.
+#[derive(Parser)]
+struct IssueCommitMsg {
+    #[clap(
+        help = "Linear issue identifier, url or number, defaults to the issue of the checked out git branch",
+        value_parser = identifier::parse
+    )]
+    id: Option<String>,
 
-    fn load() -> Result<Option<Self>> {
-        let config_file = Self::config_file()?;
-        if !config_file.exists() {
-            return Ok(None);
-        }
+    /// Commit type like 'fix' or 'feat'. By default derived from the labels of the issue.
+    #[clap(long = "type")]
+    type_: Option<String>,
+}
 
-        info!(?config_file, "loading config");
+/// Print a markdown pull request description for an issue, e.g. for `gh pr create --body-file -`.
+#[derive(Parser)]
+struct IssuePrBody {
+    #[clap(
+        help = "Linear issue identifier, url or number, defaults to the issue of the checked out git branch",
+        value_parser = identifier::parse
+    )]
+    id: Option<String>,
+}
 
-        let config = std::fs::read_to_string(config_file).context("could not read config file")?;
-        let config = toml::from_str::<Self>(config.as_str()).context("could not parse config file")?;
-        Ok(Some(config))
-    }
+/// Install a `prepare-commit-msg` hook into the current git repository that adds `Fixes ABC-123` to commit messages,
+/// taking the identifier from the branch name.
+#[derive(Parser)]
+struct HookInstall {
+    /// Overwrite an existing hook.
+    #[clap(long, action, default_value = "false")]
+    force: bool,
+}
 
-    fn save(&self) -> Result<()> {
-        let config_file = Self::config_file()?;
-        let config = toml::to_string_pretty(self).context("could not serialize config")?;
-        std::fs::write(config_file, config).context("could not write config file")?;
-        Ok(())
-    }
+/// Run by the `prepare-commit-msg` hook.
+#[derive(Parser)]
+struct HookPrepareCommitMsg {
+    file: PathBuf,
+    source: Option<String>,
+    sha: Option<String>,
 }
 
-#[tokio::main]
-async fn main() {
-    color_eyre::install().expect("color_eyre init");
+/// Remove an attachment from an issue.
+#[derive(Parser)]
+struct IssueDetach {
+    #[clap(help = "Linear issue identifier (e.g. 'L-1234'), url or number", value_parser = identifier::parse)]
+    id: String,
 
-    let args = Args::parse();
+    #[clap(help = "Id or url of the attachment")]
+    attachment: String,
+}
 
-    if !args.json_enabled() {
-        tracing_subscriber::fmt::init();
-    }
+/// Upload files and link them from a comment, the description or as attachments.
+#[derive(Parser)]
+struct IssueUpload {
+    #[clap(help = "Linear issue identifier (e.g. 'L-1234'), url or number", value_parser = identifier::parse)]
+    id: String,
+
+    #[clap(required = true)]
+    files: Vec<PathBuf>,
 
-    run(Args::parse()).await.unwrap();
+    #[clap(long = "as", default_value = "comment")]
+    target: shared::UploadTarget,
+
+    /// Text to put in front of the links to the uploaded files.
+    #[clap(short, long)]
+    message: Option<String>,
 }
 
-async fn run(args: Args) -> color_eyre::Result<()> {
-    let config = Config::load()?;
-    let api_key = args
-        .api_key
-        .clone()
-        .or_else(|| config.map(|c| c.api_key))
-        .context("no API key provided")?;
+/// Archive an issue.
+#[derive(Parser)]
+struct IssueArchive {
+    #[clap(
+        help = "Linear issue identifier (e.g. 'L-1234'), url or number, '-' to read identifiers from stdin",
+        value_parser = identifier::parse_or_stdin
+    )]
+    id: String,
 
-    let client = client::Client::new(api_key);
+    /// Do not ask for confirmation.
+    #[clap(short, long, action, default_value = "false")]
+    yes: bool,
+}
 
-    match args.cmd {
-        Command::Init => {
-            let config_file = Config::config_file()?;
-            println!("Initializing linear-cli. Will setup config file {config_file:?}",);
-            let mut config = Config::load()?.unwrap_or_default();
-            let api_key = rpassword::prompt_password("Please enter your API key: ")?;
-            config.api_key = api_key;
-            std::fs::create_dir_all(config_file.parent().unwrap())?;
-            config.save()?;
-            println!("API key saved. You can now use linear-cli.");
-        }
+/// Unarchive an issue.
+#[derive(Parser)]
+struct IssueUnarchive {
+    #[clap(
+        help = "Linear issue identifier (e.g. 'L-1234'), url or number, '-' to read identifiers from stdin",
+        value_parser = identifier::parse_or_stdin
+    )]
+    id: String,
+}
 
-        Command::Me(Me { json }) => {
-            requests::me::print(requests::me::request(&client).await, json);
-        }
+/// Move an issue to the trash. It can be restored within 30 days.
+#[derive(Parser)]
+struct IssueDelete {
+    #[clap(
+        help = "Linear issue identifier (e.g. 'L-1234'), url or number, '-' to read identifiers from stdin",
+        value_parser = identifier::parse_or_stdin
+    )]
+    id: String,
 
-        Command::Issue {
-            cmd:
-                IssueCommand::List(IssueList {
-                    n,
-                    assignee,
-                    sort_by,
-                    state,
-                    not_state,
-                    json,
-                    full_width,
-                }),
-        } => {
-            let state = match (state, not_state) {
-                (None, None) => None,
-                (state @ Some(_), None) => state,
-                (None, Some(not_state)) => {
-                    Some(shared::IssueState::iter().filter(|s| !not_state.contains(s)).collect())
-                }
-                (Some(state), Some(not_state)) => Some(state.into_iter().filter(|s| !not_state.contains(s)).collect()),
-            };
+    /// Delete the issue right away instead of moving it to the trash. Only available to admins.
+    #[clap(long, action, default_value = "false")]
+    permanently: bool,
 
-            requests::issue::list::print(
-                requests::issue::list::request()
-                    .client(&client)
-                    .maybe_n(n)
-                    .sort_by(sort_by)
-                    .maybe_assignee(assignee)
-                    .maybe_state(state)
-                    .call()
-                    .await,
-                json,
-                full_width,
-            );
-        }
+    /// Do not ask for confirmation.
+    #[clap(short, long, action, default_value = "false")]
+    yes: bool,
+}
 
-        Command::Issue {
-            cmd: IssueCommand::Show(IssueShow { id, json, full_width }),
-        } => {
-            requests::issue::show::print(
-                requests::issue::show::request()
-                    .client(&client)
-                    .issue_id(id)
-                    .call()
-                    .await,
-                json,
-                full_width,
-            );
-        }
+/// Restore an issue from the trash.
+#[derive(Parser)]
+struct IssueRestore {
+    #[clap(
+        help = "Linear issue identifier (e.g. 'L-1234'), url or number, '-' to read identifiers from stdin",
+        value_parser = identifier::parse_or_stdin
+    )]
+    id: String,
+}
 
-        Command::Issue {
-            cmd: IssueCommand::Update(IssueUpdate { id, title, state: _ }),
-        } => {
-            requests::issue::update::request()
-                .client(&client)
-                .id(id)
-                .maybe_title(title)
-                // .maybe_state(state)
-                .call()
-                .await?;
-        }
+/// Subscribe to notifications about an issue.
+#[derive(Parser)]
+struct IssueSubscribe {
+    #[clap(
+        help = "Linear issue identifier (e.g. 'L-1234'), url or number, '-' to read identifiers from stdin",
+        value_parser = identifier::parse_or_stdin
+    )]
+    id: String,
 
-        Command::Team {
-            cmd: TeamCommand::List(TeamList { json, full_width }),
-        } => {
-            requests::team::list::print(
-                requests::team::list::request().client(&client).call().await,
-                json,
-                full_width,
-            );
-        }
+    /// Display name, name or email of the user to subscribe instead of yourself.
+    #[clap(long)]
+    user: Option<String>,
+}
+
+/// Unsubscribe from notifications about an issue.
+#[derive(Parser)]
+struct IssueUnsubscribe {
+    #[clap(
+        help = "Linear issue identifier (e.g. 'L-1234'), url or number, '-' to read identifiers from stdin",
+        value_parser = identifier::parse_or_stdin
+    )]
+    id: String,
+
+    /// Display name, name or email of the user to unsubscribe instead of yourself.
+    #[clap(long)]
+    user: Option<String>,
+}
+
+/// Get a notification about an issue at a later time.
+#[derive(Parser)]
+struct IssueRemind {
+    #[clap(
+        help = "Linear issue identifier (e.g. 'L-1234'), url or number, '-' to read identifiers from stdin",
+        value_parser = identifier::parse_or_stdin
+    )]
+    id: String,
+
+    /// When to send the reminder, e.g. 'tomorrow 9am', 'friday 14:00', 'in 2 hours' or '2024-09-01 09:30'.
+    #[clap(long)]
+    at: String,
+}
+
+/// Create and check out the branch Linear suggests for an issue. Checks out the branch if it exists already.
+#[derive(Parser)]
+struct IssueBranch {
+    #[clap(help = "Linear issue identifier (e.g. 'L-1234'), url or number", value_parser = identifier::parse)]
+    id: String,
+
+    /// Also move the issue to the first started state, usually 'In Progress'.
+    #[clap(long, action, default_value = "false")]
+    start: bool,
+
+    /// Also assign the issue to yourself.
+    #[clap(long, action, default_value = "false")]
+    assign: bool,
+}
+
+/// Start working on an issue: check out its branch, move it to 'In Progress' and assign it to yourself. Same as
+/// `issue branch --start --assign`.
+#[derive(Parser)]
+struct IssueStart {
+    #[clap(help = "Linear issue identifier (e.g. 'L-1234'), url or number", value_parser = identifier::parse)]
+    id: String,
+}
+
+/// List issues.
+#[derive(Parser)]
+struct IssueList {
+    #[clap(short, long, default_value = "created")]
+    sort_by: shared::SortBy,
+
+    #[clap(flatten)]
+    filters: IssueFilters,
+
+    /// Always show the url column. By default it is omitted if identifiers are rendered as hyperlinks.
+    #[clap(long, action, default_value = "false")]
+    url: bool,
+
+    /// Same as `--format json`.
+    #[clap(long, action, default_value = "false", conflicts_with = "format")]
+    json: bool,
+
+    /// `ndjson` prints one issue per line and `ids` only the identifiers, e.g. to pipe them into `issue update -`.
+    #[clap(long)]
+    format: Option<shared::ListFormat>,
+
+    /// Print a table per group with the number of issues in it. With `--format json` the groups are printed as an
+    /// array of `{group, count, issues}`, `ndjson` and `ids` are not grouped.
+    #[clap(long)]
+    group_by: Option<shared::GroupBy>,
+
+    #[clap(long, action, default_value = "false")]
+    full_width: bool,
+}
+
+/// Create an issue.
+#[derive(Parser)]
+struct IssueCreate {
+    /// Key, name or id of the team. Defaults to `default_team` from the config file, or to picking one interactively.
+    #[clap(long)]
+    team: Option<String>,
+
+    #[clap(long)]
+    title: Option<String>,
+
+    /// Markdown description.
+    #[clap(short, long)]
+    description: Option<String>,
+
+    /// Compose the issue in `$EDITOR`, starting from the values given on the command line.
+    #[clap(short, long, action, default_value = "false")]
+    edit: bool,
+
+    #[clap(flatten)]
+    changes: IssueChanges,
+}
+
+/// Edit the title, description and other fields of an issue in `$EDITOR`.
+#[derive(Parser)]
+struct IssueEdit {
+    #[clap(
+        help = "Linear issue identifier, url or number, defaults to the issue of the checked out git branch",
+        value_parser = identifier::parse
+    )]
+    id: Option<String>,
+}
+
+/// Update an issue.
+#[derive(Parser)]
+struct IssueUpdate {
+    #[clap(
+        help = "Linear issue identifier, url or number, '-' to read identifiers from stdin. Defaults to the issue of \
+                the checked out git branch",
+        value_parser = identifier::parse_or_stdin
+    )]
+    id: Option<String>,
+
+    #[clap(long)]
+    title: Option<String>,
+
+    #[clap(flatten)]
+    changes: IssueChanges,
+
+    /// Abort if the issue was updated after this time (RFC 3339, e.g. the `updatedAt` of `issue show --json`).
+    #[clap(long, value_name = "UPDATED_AT")]
+    if_unmodified_since: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Abort unless the issue is in this workflow state, given by name or type.
+    #[clap(long, value_name = "STATE")]
+    expect_state: Option<String>,
+}
+
+/// Apply the same change to many issues at once. Issues are given as identifiers, on stdin (one per line, or `-`) or
+/// selected with the `--filter-*` options.
+#[derive(Parser)]
+struct IssueBulkUpdate {
+    #[clap(
+        help = "Linear issue identifiers (e.g. 'L-1234'), '-' to read them from stdin",
+        value_parser = identifier::parse_or_stdin
+    )]
+    ids: Vec<String>,
+
+    #[clap(flatten)]
+    filter: IssueFilter,
+
+    #[clap(flatten)]
+    changes: IssueChanges,
+
+    #[clap(long, action, default_value = "false")]
+    json: bool,
+}
+
+/// Which issues `issue list` and `tui` show.
+#[derive(clap::Args)]
+struct IssueFilters {
+    #[clap(short, long = "limit")]
+    n: Option<usize>,
+
+    #[clap(short, long)]
+    assignee: Option<String>,
+
+    #[clap(long, value_delimiter = ',')]
+    state: Option<Vec<shared::IssueState>>,
+
+    #[clap(long, value_delimiter = ',')]
+    not_state: Option<Vec<shared::IssueState>>,
+
+    /// Only list sub-issues of the given issue.
+    #[clap(long, value_parser = identifier::parse)]
+    parent: Option<String>,
+
+    /// Only list issues that are blocked by another issue which is not done yet.
+    #[clap(long, action, default_value = "false")]
+    blocked: bool,
+
+    /// Also list archived issues.
+    #[clap(long, action, default_value = "false")]
+    include_archived: bool,
+}
+
+/// Fields that can be set by `issue create` and changed by `issue update` and `issue bulk-update`.
+#[derive(clap::Args)]
+struct IssueChanges {
+    /// Workflow state name (e.g. 'In Progress') or type (e.g. 'started'). Without a value, pick one interactively.
+    #[clap(long, num_args = 0..=1, default_missing_value = "")]
+    state: Option<String>,
+
+    /// Display name, name or email of the assignee, 'me' or 'none'. Without a value, pick one interactively.
+    #[clap(long, num_args = 0..=1, default_missing_value = "")]
+    assignee: Option<String>,
+
+    /// Comma separated label names, replacing the current labels. 'none' removes all labels.
+    #[clap(long, value_delimiter = ',')]
+    labels: Option<Vec<String>>,
+
+    #[clap(long)]
+    priority: Option<shared::Priority>,
+
+    /// Cycle number or name, 'current', 'next', 'previous' or 'none'.
+    #[clap(long)]
+    cycle: Option<String>,
+
+    /// Project name or 'none'.
+    #[clap(long)]
+    project: Option<String>,
+}
+
+impl IssueChanges {
+    fn into_changes(self, title: Option<String>) -> requests::issue::changes::Changes {
+        let IssueChanges {
+            state,
+            assignee,
+            labels,
+            priority,
+            cycle,
+            project,
+        } = self;
+        requests::issue::changes::Changes {
+            title,
+            state,
+            assignee,
+            labels,
+            priority,
+            cycle,
+            project,
+            ..Default::default()
+        }
+    }
+}
+
+/// Selects issues the same way `issue list` does.
+#[derive(clap::Args)]
+struct IssueFilter {
+    #[clap(long = "filter-assignee", id = "filter_assignee", value_name = "ASSIGNEE")]
+    assignee: Option<String>,
+
+    #[clap(long = "filter-state", id = "filter_state", value_name = "STATE", value_delimiter = ',')]
+    state: Option<Vec<shared::IssueState>>,
+
+    #[clap(
+        long = "filter-not-state",
+        id = "filter_not_state",
+        value_name = "STATE",
+        value_delimiter = ','
+    )]
+    not_state: Option<Vec<shared::IssueState>>,
+
+    #[clap(
+        long = "filter-parent",
+        id = "filter_parent",
+        value_name = "PARENT",
+        value_parser = identifier::parse
+    )]
+    parent: Option<String>,
+
+    #[clap(long = "filter-blocked", id = "filter_blocked", action, default_value = "false")]
+    blocked: bool,
+}
+
+impl IssueFilter {
+    fn is_empty(&self) -> bool {
+        let IssueFilter {
+            assignee,
+            state,
+            not_state,
+            parent,
+            blocked,
+        } = self;
+        assignee.is_none() && state.is_none() && not_state.is_none() && parent.is_none() && !blocked
+    }
+}
+
+fn combine_state_filters(
+    state: Option<Vec<shared::IssueState>>,
+    not_state: Option<Vec<shared::IssueState>>,
+) -> Option<Vec<shared::IssueState>> {
+    match (state, not_state) {
+        (None, None) => None,
+        (state @ Some(_), None) => state,
+        (None, Some(not_state)) => Some(shared::IssueState::iter().filter(|s| !not_state.contains(s)).collect()),
+        (Some(state), Some(not_state)) => Some(state.into_iter().filter(|s| !not_state.contains(s)).collect()),
+    }
+}
+
+/// The team given on the command line, or `default_team` from the config file. Without either the team is picked
+/// interactively if possible, the resolver asks for a team if the name is empty.
+fn team_or_default(team: Option<String>) -> Result<String> {
+    match team.or_else(|| identifier::default_team().map(str::to_string)) {
+        Some(team) => Ok(team),
+        None if prompt::interactive() => Ok(String::new()),
+        None => eyre::bail!("no team given, pass --team or set `default_team` in the config file"),
+    }
+}
+
+/// Read issue identifiers from stdin, one per line. Lines can also be JSON objects with an `identifier` or `id` field,
+/// like the ones printed by `issue list --format ndjson`.
+fn read_issue_ids_from_stdin() -> Result<Vec<String>> {
+    let mut ids = Vec::new();
+    for line in std::io::stdin().lines() {
+        let line = line.context("could not read issue identifiers from stdin")?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let id = if line.starts_with('{') {
+            let issue = serde_json::from_str::<serde_json::Value>(line)
+                .with_context(|| format!("could not parse {line:?} read from stdin"))?;
+            issue
+                .get("identifier")
+                .or_else(|| issue.get("id"))
+                .and_then(|id| id.as_str())
+                .with_context(|| format!("{line:?} read from stdin has no identifier"))?
+                .to_string()
+        } else {
+            line.to_string()
+        };
+        ids.push(identifier::parse(&id).map_err(|e| eyre::eyre!(e))?);
+    }
+    Ok(ids)
+}
+
+/// The issues a command acts on: the given one, the ones read from stdin if it is `-`, or the issue of the checked out
+/// git branch if none is given.
+async fn issue_ids(client: &client::Client, id: Option<String>) -> Result<Vec<String>> {
+    match id {
+        Some(id) if id == "-" => {
+            let ids = read_issue_ids_from_stdin()?;
+            if ids.is_empty() {
+                eyre::bail!("no issue identifiers on stdin");
+            }
+            Ok(ids)
+        }
+        id => Ok(vec![requests::issue::current::or_current(client, id).await?]),
+    }
+}
+
+/// Apply `changes` to a single issue, for `issue update`, `issue edit`, `issue branch` and `issue start`. Returns what
+/// changed, for the journal.
+async fn update_issue(
+    client: &client::Client,
+    resolver: &mut requests::issue::changes::Resolver<'_>,
+    id: String,
+    changes: &requests::issue::changes::Changes,
+    precondition: &requests::issue::update::Precondition,
+) -> Result<journal::IssueChange> {
+    let issue = requests::issue::show::request()
+        .client(client)
+        .issue_id(id)
+        .call()
+        .await?;
+    let input = resolver.resolve(&issue.team.id, changes).await?;
+    if client.is_dry_run() {
+        let diff = resolver.diff(&(&issue).into(), &input).await?;
+        requests::issue::changes::print_diff(&issue.identifier, &issue.title, &diff);
+    }
+
+    // Resolving the changes can take a few requests, check against the latest version of the issue.
+    if !precondition.is_empty() {
+        let latest = requests::issue::show::request()
+            .client(client)
+            .issue_id(issue.id.clone())
+            .call()
+            .await?;
+        precondition.check(&latest)?;
+    }
+    let before = requests::issue::changes::Snapshot::from(&issue).revert(&input);
+    let issue = requests::issue::update::request()
+        .client(client)
+        .id(issue.id)
+        .input(&input)
+        .call()
+        .await?;
+    Ok(journal::IssueChange {
+        id: issue.id,
+        identifier: issue.identifier,
+        before,
+        after: input,
+        updated_at: Some(issue.updated_at),
+    })
+}
+
+/// An issue written in `$EDITOR`, with the changes it makes resolved for the issue's team.
+struct EditedIssue {
+    changes: requests::issue::changes::Changes,
+    input: requests::issue::update::Input,
+    /// What the user wrote, to keep it if the issue can't be saved.
+    text: String,
+}
+
+/// Let the user edit `document` in `$EDITOR`. `changes` turns the edited document into changes, which are resolved for
+/// the team `team_id`. If any of that fails, e.g. on a YAML error or an unknown label, the user can fix it instead of
+/// losing what they wrote. When they give up, the text is kept in a file.
+async fn edit_document(
+    name: &str,
+    document: &requests::issue::front_matter::Document,
+    changes: impl Fn(&requests::issue::front_matter::Document) -> Result<requests::issue::changes::Changes>,
+    resolver: &mut requests::issue::changes::Resolver<'_>,
+    team_id: &str,
+) -> Result<EditedIssue> {
+    let mut text = document.render();
+    loop {
+        text = editor::edit(name, &text)?;
+        let res = async {
+            let edited = requests::issue::front_matter::Document::parse(&text)?;
+            let changes = changes(&edited)?;
+            let input = resolver.resolve(team_id, &changes).await?;
+            Ok::<_, eyre::Report>((changes, input))
+        }
+        .await;
+        match res {
+            Ok((changes, input)) => return Ok(EditedIssue { changes, input, text }),
+            Err(err) => {
+                eprintln!("{err:#}");
+                if !prompt::confirm("Edit again?").unwrap_or(false) {
+                    return Err(keep_edited(name, &text, err));
+                }
+            }
+        }
+    }
+}
+
+/// Save what the user wrote in `$EDITOR` when it couldn't be used, and point to it in the error.
+fn keep_edited(name: &str, text: &str, err: eyre::Report) -> eyre::Report {
+    match editor::keep(name, text) {
+        Ok(path) => err.wrap_err(format!("what you wrote was saved to {}", path.display())),
+        Err(keep_err) => err.wrap_err(format!("what you wrote could not be saved: {keep_err:#}")),
+    }
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct Config {
+    api_key: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    date_format: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timezone: Option<String>,
+
+    /// Key of the team that bare issue numbers like `123` belong to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default_team: Option<String>,
+
+    /// Set to `false` to never pipe output through `$PAGER`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pager: Option<bool>,
+}
+
+impl Config {
+    fn config_file() -> Result<PathBuf> {
+        let dir = directories::ProjectDirs::from("app", "linear", "linear-cli")
+            .context("could not determine project directories")?;
+        let config_dir = dir.config_dir();
+        let config_file = config_dir.join("config.toml");
+        Ok(config_file)
+    }
+
+    fn load() -> Result<Option<Self>> {
+        let config_file = Self::config_file()?;
+        if !config_file.exists() {
+            return Ok(None);
+        }
+
+        info!(?config_file, "loading config");
+
+        let config = std::fs::read_to_string(config_file).context("could not read config file")?;
+        let config = toml::from_str::<Self>(config.as_str()).context("could not parse config file")?;
+        Ok(Some(config))
+    }
+
+    fn save(&self) -> Result<()> {
+        let config_file = Self::config_file()?;
+        let config = toml::to_string_pretty(self).context("could not serialize config")?;
+        std::fs::write(config_file, config).context("could not write config file")?;
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    color_eyre::install().expect("color_eyre init");
+
+    // Bare issue numbers are completed with the default team while the arguments are parsed. Errors loading the
+    // config are reported by `run`.
+    if let Ok(Some(config)) = Config::load() {
+        identifier::init(config.default_team);
+    }
+
+    let args = Args::parse();
+
+    if !args.json_enabled() {
+        tracing_subscriber::fmt::init();
+    }
+
+    style::init(args.color, args.hyperlinks);
+
+    match run(Args::parse()).await {
+        Err(err) if err.is::<client::DryRun>() => {}
+        res => res.unwrap(),
+    }
+}
+
+async fn run(args: Args) -> color_eyre::Result<()> {
+    // The hook runs on every commit and must work without network access, an API key or even a valid config file.
+    if let Command::Hook { cmd } = args.cmd {
+        match cmd {
+            HookCommand::Install(HookInstall { force }) => {
+                let path = hook::install(force)?;
+                println!("installed {}", path.display());
+            }
+            HookCommand::PrepareCommitMsg(HookPrepareCommitMsg { file, source, sha: _ }) => {
+                hook::prepare_commit_msg(&file, source.as_deref())?;
+            }
+        }
+        return Ok(());
+    }
+
+    let config = Config::load()?;
+
+    let date_format = match (args.date_format.clone(), config.as_ref().and_then(|c| c.date_format.as_deref())) {
+        (Some(format), _) => format,
+        (None, Some(format)) => format.parse().map_err(|e: String| eyre::eyre!(e))?,
+        (None, None) => Default::default(),
+    };
+    let timezone = match (args.timezone.clone(), config.as_ref().and_then(|c| c.timezone.as_deref())) {
+        (Some(timezone), _) => timezone,
+        (None, Some(timezone)) => timezone.parse().map_err(|e: String| eyre::eyre!(e))?,
+        (None, None) => Default::default(),
+    };
+    dates::init(date_format, timezone);
+
+    let pager_enabled = args.pager_enabled() && config.as_ref().and_then(|c| c.pager).unwrap_or(true);
+    let _pager = if pager_enabled { pager::start() } else { None };
+
+    let api_key = args
+        .api_key
+        .clone()
+        .or_else(|| config.map(|c| c.api_key))
+        .context("no API key provided")?;
+
+    let client = client::Client::new(api_key).dry_run(args.dry_run);
+
+    match args.cmd {
+        Command::Init => {
+            let config_file = Config::config_file()?;
+            println!("Initializing linear-cli. Will setup config file {config_file:?}",);
+            let mut config = Config::load()?.unwrap_or_default();
+            let api_key = rpassword::prompt_password("Please enter your API key: ")?;
+            config.api_key = api_key;
+            std::fs::create_dir_all(config_file.parent().unwrap())?;
+            config.save()?;
+            println!("API key saved. You can now use linear-cli.");
+        }
+
+        Command::Me(Me { json }) => {
+            requests::me::print(requests::me::request(&client).await, json);
+        }
+
+        Command::Issue {
+            cmd:
+                IssueCommand::List(IssueList {
+                    sort_by,
+                    filters:
+                        IssueFilters {
+                            n,
+                            assignee,
+                            state,
+                            not_state,
+                            parent,
+                            blocked,
+                            include_archived,
+                        },
+                    url,
+                    json,
+                    format,
+                    group_by,
+                    full_width,
+                }),
+        } => {
+            let state = combine_state_filters(state, not_state);
+            let format = if json { shared::ListFormat::Json } else { format.unwrap_or_default() };
+
+            requests::issue::list::print(
+                requests::issue::list::request()
+                    .client(&client)
+                    .maybe_n(n)
+                    .sort_by(sort_by)
+                    .maybe_assignee(assignee)
+                    .maybe_state(state)
+                    .maybe_parent(parent)
+                    .blocked(blocked)
+                    .include_archived(include_archived)
+                    .call()
+                    .await,
+                format,
+                group_by,
+                full_width,
+                url || !style::hyperlinks_enabled(),
+            );
+        }
+
+        Command::Issue {
+            cmd:
+                IssueCommand::Show(IssueShow {
+                    id,
+                    children,
+                    json,
+                    full_width,
+                }),
+        } => {
+            // Several issues are printed as one JSON array, so that the output stays a single document.
+            let ids = issue_ids(&client, id).await?;
+            let json_array = json && ids.len() > 1;
+            let mut values = Vec::new();
+            for (i, id) in ids.into_iter().enumerate() {
+                if i > 0 && !json {
+                    println!();
+                }
+                let children = if children {
+                    Some(
+                        requests::issue::tree::request()
+                            .client(&client)
+                            .issue_id(id.clone())
+                            .call()
+                            .await?,
+                    )
+                } else {
+                    None
+                };
+                let res = requests::issue::show::request()
+                    .client(&client)
+                    .issue_id(id)
+                    .call()
+                    .await;
+                match res {
+                    Ok(issue) if json_array => values.push(requests::issue::show::to_json(&issue, children)),
+                    res => requests::issue::show::print(res, children, json, full_width),
+                }
+            }
+            if json_array {
+                println!("{}", serde_json::to_string_pretty(&values).unwrap());
+            }
+        }
+
+        Command::Issue {
+            cmd: IssueCommand::Tree(IssueTree { id, json, full_width }),
+        } => {
+            let ids = issue_ids(&client, Some(id)).await?;
+            let json_array = json && ids.len() > 1;
+            let mut values = Vec::new();
+            for (i, id) in ids.into_iter().enumerate() {
+                if i > 0 && !json {
+                    println!();
+                }
+                match requests::issue::tree::request().client(&client).issue_id(id).call().await {
+                    Ok(node) if json_array => values.push(node),
+                    res => requests::issue::tree::print(res, json, full_width),
+                }
+            }
+            if json_array {
+                println!("{}", serde_json::to_string_pretty(&values).unwrap());
+            }
+        }
+
+        Command::Issue {
+            cmd: IssueCommand::History(IssueHistory { id, json, full_width }),
+        } => {
+            let ids = issue_ids(&client, Some(id)).await?;
+            let json_array = json && ids.len() > 1;
+            let mut values = Vec::new();
+            for (i, id) in ids.into_iter().enumerate() {
+                if i > 0 && !json {
+                    println!();
+                }
+                match requests::issue::history::request().client(&client).issue_id(id).call().await {
+                    Ok(history) if json_array => values.push(history),
+                    res => requests::issue::history::print(res, json, full_width),
+                }
+            }
+            if json_array {
+                println!("{}", serde_json::to_string_pretty(&values).unwrap());
+            }
+        }
+
+        Command::Issue {
+            cmd:
+                IssueCommand::Create(IssueCreate {
+                    team,
+                    title,
+                    description,
+                    edit,
+                    changes,
+                }),
+        } => {
+            use requests::issue::front_matter::Document;
+
+            let team = team_or_default(team)?;
+            let mut changes = changes.into_changes(title);
+            changes.description = description;
+            let mut resolver = requests::issue::changes::Resolver::new(&client);
+            let team_id = resolver.team(&team).await?;
+
+            let (input, text) = if edit {
+                let edited = edit_document(
+                    "issue.md",
+                    &Document::from(&changes),
+                    // Cycle and project are not part of the front matter, they can only be given on the command line.
+                    |edited| {
+                        Ok(requests::issue::changes::Changes {
+                            cycle: changes.cycle.clone(),
+                            project: changes.project.clone(),
+                            ..Document::default().changes(edited)?
+                        })
+                    },
+                    &mut resolver,
+                    &team_id,
+                )
+                .await?;
+                (edited.input, Some(edited.text))
+            } else {
+                if changes.title.is_none() {
+                    eyre::bail!("an issue needs a title, pass --title or --edit");
+                }
+                (resolver.resolve(&team_id, &changes).await?, None)
+            };
+
+            let issue = match requests::issue::create::request()
+                .client(&client)
+                .team_id(team_id)
+                .input(&input)
+                .call()
+                .await
+            {
+                Err(err) if !err.is::<client::DryRun>() && text.is_some() => {
+                    return Err(keep_edited("issue.md", text.as_deref().unwrap_or_default(), err));
+                }
+                res => res?,
+            };
+            println!("{} created: {}", issue.identifier, issue.url);
+            journal::record(journal::Change::Create {
+                issue_id: issue.id,
+                identifier: issue.identifier,
+            });
+        }
+
+        Command::Issue {
+            cmd: IssueCommand::Edit(IssueEdit { id }),
+        } => {
+            use requests::issue::front_matter::Document;
+
+            let issue = requests::issue::show::request()
+                .client(&client)
+                .issue_id(requests::issue::current::or_current(&client, id).await?)
+                .call()
+                .await?;
+            let original = Document::from(&issue);
+            let name = format!("{}.md", issue.identifier);
+            let mut resolver = requests::issue::changes::Resolver::new(&client);
+            let edited = edit_document(
+                &name,
+                &original,
+                |edited| original.changes(edited),
+                &mut resolver,
+                &issue.team.id,
+            )
+            .await?;
+            if edited.changes.is_empty() {
+                println!("{} unchanged", issue.identifier);
+                return Ok(());
+            }
+
+            // Don't overwrite changes someone else made while the editor was open.
+            let precondition = requests::issue::update::Precondition {
+                unmodified_since: Some(issue.updated_at),
+                state: None,
+            };
+            let change = match update_issue(&client, &mut resolver, issue.id, &edited.changes, &precondition).await {
+                Err(err) if !err.is::<client::DryRun>() => return Err(keep_edited(&name, &edited.text, err)),
+                res => res?,
+            };
+            println!("{} updated", change.identifier);
+            journal::record(journal::Change::Update { issues: vec![change] });
+        }
+
+        Command::Issue {
+            cmd:
+                IssueCommand::Update(IssueUpdate {
+                    id,
+                    title,
+                    changes,
+                    if_unmodified_since,
+                    expect_state,
+                }),
+        } => {
+            let changes = changes.into_changes(title);
+            let precondition = requests::issue::update::Precondition {
+                unmodified_since: if_unmodified_since,
+                state: expect_state,
+            };
+            if changes.is_empty() {
+                eyre::bail!("nothing to update");
+            }
+
+            let mut resolver = requests::issue::changes::Resolver::new(&client);
+            let ids = issue_ids(&client, id).await?;
+            let total = ids.len();
+            let mut updated = Vec::new();
+            let mut failed = Vec::new();
+            for id in ids {
+                match update_issue(&client, &mut resolver, id.clone(), &changes, &precondition).await {
+                    Ok(change) => {
+                        println!("{} updated", change.identifier);
+                        updated.push(change);
+                    }
+                    // Show the mutations for all issues, not just the first one.
+                    Err(err) if err.is::<client::DryRun>() => continue,
+                    // A failing issue doesn't stop the others, like in `issue bulk-update`.
+                    Err(err) if total > 1 => {
+                        eprintln!("{id}: FAILED: {err:#}");
+                        failed.push(id);
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+
+            if !updated.is_empty() {
+                journal::record(journal::Change::Update { issues: updated });
+            }
+            if !failed.is_empty() {
+                eyre::bail!("{} of {total} issues could not be updated: {}", failed.len(), failed.join(", "));
+            }
+            if client.is_dry_run() {
+                return Err(client::DryRun.into());
+            }
+        }
+
+        Command::Issue {
+            cmd:
+                IssueCommand::BulkUpdate(IssueBulkUpdate {
+                    ids,
+                    filter,
+                    changes,
+                    json,
+                }),
+        } => {
+            let changes = changes.into_changes(None);
+            if changes.is_empty() {
+                eyre::bail!("nothing to update");
+            }
+
+            // `-` is replaced by the identifiers read from stdin, which is also read if nothing else selects issues.
+            let read_stdin = ids.iter().any(|id| id == "-")
+                || (ids.is_empty() && filter.is_empty() && !std::io::stdin().is_terminal());
+            let mut stdin_ids = if read_stdin { read_issue_ids_from_stdin()? } else { Vec::new() };
+            let mut ids = ids
+                .into_iter()
+                .flat_map(|id| if id == "-" { std::mem::take(&mut stdin_ids) } else { vec![id] })
+                .collect::<Vec<_>>();
+            ids.append(&mut stdin_ids);
+
+            let filter = (!filter.is_empty()).then(|| {
+                let IssueFilter {
+                    assignee,
+                    state,
+                    not_state,
+                    parent,
+                    blocked,
+                } = filter;
+                requests::issue::bulk_update::Filter {
+                    assignee,
+                    state: combine_state_filters(state, not_state),
+                    parent,
+                    blocked,
+                }
+            });
+            let (targets, not_found) = requests::issue::bulk_update::targets()
+                .client(&client)
+                .ids(ids)
+                .maybe_filter(filter)
+                .call()
+                .await?;
+            if targets.is_empty() && not_found.is_empty() {
+                eyre::bail!("no issues to update, pass identifiers, pipe them to stdin or use --filter-* options");
+            }
+
+            let outcomes = requests::issue::bulk_update::request()
+                .client(&client)
+                .targets(targets)
+                .changes(&changes)
+                .call()
+                .await
+                .map(|mut outcomes| {
+                    outcomes.extend(not_found.iter().cloned());
+                    outcomes
+                });
+            if client.is_dry_run() {
+                for outcome in outcomes? {
+                    eprintln!("{}: {}", outcome.identifier, outcome.error.unwrap_or_default());
+                }
+                return Ok(());
+            }
+
+            if let Ok(outcomes) = &outcomes {
+                let issues = outcomes
+                    .iter()
+                    .filter(|o| o.updated)
+                    .map(|o| journal::IssueChange {
+                        id: o.id.clone(),
+                        identifier: o.identifier.clone(),
+                        before: o.before.clone(),
+                        after: o.after.clone(),
+                        updated_at: o.updated_at,
+                    })
+                    .collect::<Vec<_>>();
+                if !issues.is_empty() {
+                    journal::record(journal::Change::Update { issues });
+                }
+            }
+            requests::issue::bulk_update::print(outcomes, json);
+        }
+
+        Command::Issue {
+            cmd:
+                IssueCommand::Relate(IssueRelate {
+                    id,
+                    blocks,
+                    blocked_by,
+                    duplicate_of,
+                    related,
+                }),
+        } => {
+            use requests::issue::relation::Relation;
+
+            let (relation, other) = match (blocks, blocked_by, duplicate_of, related) {
+                (Some(other), _, _, _) => (Relation::Blocks, other),
+                (_, Some(other), _, _) => (Relation::BlockedBy, other),
+                (_, _, Some(other), _) => (Relation::DuplicateOf, other),
+                (_, _, _, Some(other)) => (Relation::Related, other),
+                _ => unreachable!("clap requires one relation"),
+            };
+            for id in issue_ids(&client, Some(id)).await? {
+                let (stored, issue, other) = match requests::issue::relation::create()
+                    .client(&client)
+                    .issue_id(id)
+                    .relation(relation)
+                    .other_id(other.clone())
+                    .call()
+                    .await
+                {
+                    Err(err) if err.is::<client::DryRun>() => continue,
+                    res => res?,
+                };
+                println!("{issue} {relation} {other}");
+                journal::record(journal::Change::Relate {
+                    identifier: issue,
+                    other,
+                    relation: stored,
+                });
+            }
+            if client.is_dry_run() {
+                return Err(client::DryRun.into());
+            }
+        }
+
+        Command::Issue {
+            cmd: IssueCommand::Unrelate(IssueUnrelate { id, other }),
+        } => {
+            for id in issue_ids(&client, Some(id)).await? {
+                let relations = match requests::issue::relation::delete()
+                    .client(&client)
+                    .issue_id(id.clone())
+                    .other_id(other.clone())
+                    .call()
+                    .await
+                {
+                    Err(err) if err.is::<client::DryRun>() => continue,
+                    res => res?,
+                };
+                println!("{id}: removed {} relation(s)", relations.len());
+                journal::record(journal::Change::Unrelate {
+                    identifier: id,
+                    other: other.clone(),
+                    relations,
+                });
+            }
+            if client.is_dry_run() {
+                return Err(client::DryRun.into());
+            }
+        }
+
+        Command::Issue {
+            cmd:
+                IssueCommand::Attach(IssueAttach {
+                    id,
+                    url,
+                    title,
+                    subtitle,
+                }),
+        } => {
+            let (id, url) = match (id, url) {
+                (Some(id), Some(url)) => (identifier::parse(&id).map_err(|e| eyre::eyre!(e))?, url),
+                (None, Some(url)) => (requests::issue::current::request().client(&client).call().await?, url),
+                // A lone identifier means the url was forgotten, it must not be attached as a link.
+                (Some(url), None) if reqwest::Url::parse(&url).is_ok() => {
+                    (requests::issue::current::request().client(&client).call().await?, url)
+                }
+                (Some(_), None) | (None, None) => eyre::bail!("no url given"),
+            };
+            let attachment = requests::issue::attachment::create()
+                .client(&client)
+                .issue_id(id.clone())
+                .url(url)
+                .maybe_title(title)
+                .maybe_subtitle(subtitle)
+                .call()
+                .await?;
+            println!("attached {} ({})", attachment.title, attachment.id);
+            journal::record(journal::Change::Attach {
+                identifier: id,
+                attachment,
+            });
+        }
+
+        Command::Issue {
+            cmd: IssueCommand::Comment(IssueComment { id, message }),
+        } => {
+            let body = match message {
+                Some(message) => message,
+                None if id.as_deref() == Some("-") => {
+                    eyre::bail!("pass the comment with --message when reading identifiers from stdin")
+                }
+                None if !std::io::stdin().is_terminal() => {
+                    std::io::read_to_string(std::io::stdin()).context("could not read comment from stdin")?
+                }
+                None => eyre::bail!("no comment given, pass --message or pipe it to stdin"),
+            };
+            if body.trim().is_empty() {
+                eyre::bail!("comment is empty");
+            }
+
+            for id in issue_ids(&client, id).await? {
+                let comment = match requests::issue::comment::create()
+                    .client(&client)
+                    .issue_id(id.clone())
+                    .body(body.clone())
+                    .call()
+                    .await
+                {
+                    Err(err) if err.is::<client::DryRun>() => continue,
+                    res => res?,
+                };
+                journal::record(journal::Change::Comment {
+                    identifier: id,
+                    comment_id: comment.id,
+                });
+                println!("{}", comment.url);
+            }
+            if client.is_dry_run() {
+                return Err(client::DryRun.into());
+            }
+        }
+
+        Command::Issue {
+            cmd: IssueCommand::CommitMsg(IssueCommitMsg { id, type_ }),
+        } => {
+            let issue = requests::issue::show::request()
+                .client(&client)
+                .issue_id(requests::issue::current::or_current(&client, id).await?)
+                .call()
+                .await?;
+            print!("{}", requests::issue::template::commit_msg(&issue, type_.as_deref()));
+        }
+
+        Command::Issue {
+            cmd: IssueCommand::PrBody(IssuePrBody { id }),
+        } => {
+            let issue = requests::issue::show::request()
+                .client(&client)
+                .issue_id(requests::issue::current::or_current(&client, id).await?)
+                .call()
+                .await?;
+            print!("{}", requests::issue::template::pr_body(&issue));
+        }
+
+        Command::Issue {
+            cmd: IssueCommand::Detach(IssueDetach { id, attachment }),
+        } => {
+            let attachment = requests::issue::attachment::delete()
+                .client(&client)
+                .issue_id(id.clone())
+                .attachment(attachment)
+                .call()
+                .await?;
+            println!("removed {} ({})", attachment.title, attachment.url);
+            journal::record(journal::Change::Detach {
+                identifier: id,
+                attachment,
+            });
+        }
+
+        Command::Issue {
+            cmd:
+                IssueCommand::Upload(IssueUpload {
+                    id,
+                    files,
+                    target,
+                    message,
+                }),
+        } => {
+            use requests::issue::upload::Linked;
+
+            let upload = requests::issue::upload::request()
+                .client(&client)
+                .issue_id(id)
+                .files(files)
+                .target(target)
+                .maybe_message(message)
+                .call()
+                .await?;
+            match upload.linked {
+                Linked::Comment(comment) => journal::record(journal::Change::Comment {
+                    identifier: upload.identifier,
+                    comment_id: comment.id,
+                }),
+                Linked::Description {
+                    before,
+                    after,
+                    updated_at,
+                } => journal::record(journal::Change::Update {
+                    issues: vec![journal::IssueChange {
+                        id: upload.issue_id,
+                        identifier: upload.identifier,
+                        before: requests::issue::update::Input {
+                            description: Some(before),
+                            ..Default::default()
+                        },
+                        after: requests::issue::update::Input {
+                            description: Some(after),
+                            ..Default::default()
+                        },
+                        updated_at: Some(updated_at),
+                    }],
+                }),
+                Linked::Attachments(attachments) => {
+                    for attachment in attachments {
+                        journal::record(journal::Change::Attach {
+                            identifier: upload.identifier.clone(),
+                            attachment,
+                        });
+                    }
+                }
+            }
+            for file in upload.files {
+                println!("{}", file.asset_url);
+            }
+        }
+
+        Command::Issue {
+            cmd:
+                cmd @ (IssueCommand::Archive(_)
+                | IssueCommand::Unarchive(_)
+                | IssueCommand::Delete(_)
+                | IssueCommand::Restore(_)),
+        } => {
+            use requests::issue::archive::Action;
+
+            let (id, action, yes) = match cmd {
+                IssueCommand::Archive(IssueArchive { id, yes }) => (id, Action::Archive, yes),
+                IssueCommand::Unarchive(IssueUnarchive { id }) => (id, Action::Unarchive, false),
+                IssueCommand::Delete(IssueDelete { id, permanently, yes }) => (id, Action::Delete { permanently }, yes),
+                IssueCommand::Restore(IssueRestore { id }) => (id, Action::Restore, false),
+                _ => unreachable!(),
+            };
+
+            let mut issues = Vec::new();
+            for id in issue_ids(&client, Some(id)).await? {
+                issues.push(
+                    requests::issue::show::request()
+                        .client(&client)
+                        .issue_id(id)
+                        .call()
+                        .await?,
+                );
+            }
+            if client.is_dry_run() {
+                for issue in &issues {
+                    println!("{} {}: {action}", issue.identifier, issue.title);
+                }
+            } else if action.is_destructive() && !yes {
+                let question = match issues.as_slice() {
+                    [issue] => format!("{action} {} \"{}\"?", issue.identifier, issue.title),
+                    issues => format!("{action} {} issues?", issues.len()),
+                };
+                if !prompt::confirm(question)? {
+                    return Ok(());
+                }
+            }
+
+            for issue in issues {
+                match requests::issue::archive::request()
+                    .client(&client)
+                    .id(issue.id.clone())
+                    .action(action)
+                    .call()
+                    .await
+                {
+                    Err(err) if err.is::<client::DryRun>() => continue,
+                    res => res?,
+                };
+                println!("{} {}", issue.identifier, action.past_tense());
+                journal::record(journal::Change::Archive {
+                    issue_id: issue.id,
+                    identifier: issue.identifier,
+                    action,
+                });
+            }
+            if client.is_dry_run() {
+                return Err(client::DryRun.into());
+            }
+        }
+
+        Command::Issue {
+            cmd:
+                cmd @ (IssueCommand::Subscribe(_) | IssueCommand::Unsubscribe(_)),
+        } => {
+            let (id, user, subscribe) = match cmd {
+                IssueCommand::Subscribe(IssueSubscribe { id, user }) => (id, user, true),
+                IssueCommand::Unsubscribe(IssueUnsubscribe { id, user }) => (id, user, false),
+                _ => unreachable!(),
+            };
+
+            let user_id = match user {
+                // `Resolver::user` resolves `none` to no user, which would (un)subscribe the authenticated user.
+                Some(user) if user.trim().eq_ignore_ascii_case("none") => {
+                    eyre::bail!("--user needs a user, leave it out to (un)subscribe yourself")
+                }
+                Some(user) => requests::issue::changes::Resolver::new(&client).user(&user).await?,
+                None => None,
+            };
+            for id in issue_ids(&client, Some(id)).await? {
+                let was_subscribed = requests::issue::subscription::is_subscribed()
+                    .client(&client)
+                    .issue_id(id.clone())
+                    .maybe_user_id(user_id.as_deref())
+                    .call()
+                    .await?;
+                let res = if subscribe {
+                    requests::issue::subscription::subscribe()
+                        .client(&client)
+                        .issue_id(id)
+                        .maybe_user_id(user_id.clone())
+                        .call()
+                        .await
+                } else {
+                    requests::issue::subscription::unsubscribe()
+                        .client(&client)
+                        .issue_id(id)
+                        .maybe_user_id(user_id.clone())
+                        .call()
+                        .await
+                };
+                let identifier = match res {
+                    Err(err) if err.is::<client::DryRun>() => continue,
+                    res => res?,
+                };
+                println!("{} {identifier}", if subscribe { "subscribed to" } else { "unsubscribed from" });
+                journal::record(journal::Change::Subscribe {
+                    identifier,
+                    user_id: user_id.clone(),
+                    subscribed: subscribe,
+                    was_subscribed: Some(was_subscribed),
+                });
+            }
+            if client.is_dry_run() {
+                return Err(client::DryRun.into());
+            }
+        }
+
+        Command::Issue {
+            cmd: IssueCommand::Remind(IssueRemind { id, at }),
+        } => {
+            let at = dates::parse(&at).map_err(|e| eyre::eyre!(e))?;
+            for id in issue_ids(&client, Some(id)).await? {
+                let identifier = match requests::issue::subscription::remind()
+                    .client(&client)
+                    .issue_id(id)
+                    .at(at)
+                    .call()
+                    .await
+                {
+                    Err(err) if err.is::<client::DryRun>() => continue,
+                    res => res?,
+                };
+                println!("reminder for {identifier} set for {}", dates::fmt(at));
+            }
+            if client.is_dry_run() {
+                return Err(client::DryRun.into());
+            }
+        }
+
+        Command::Issue {
+            cmd: cmd @ (IssueCommand::Branch(_) | IssueCommand::Start(_)),
+        } => {
+            let (id, start, assign) = match cmd {
+                IssueCommand::Branch(IssueBranch { id, start, assign }) => (id, start, assign),
+                IssueCommand::Start(IssueStart { id }) => (id, true, true),
+                _ => unreachable!(),
+            };
+
+            let issue = requests::issue::show::request()
+                .client(&client)
+                .issue_id(id)
+                .call()
+                .await?;
+
+            if client.is_dry_run() {
+                println!("would check out {}", issue.branch_name);
+            } else if git::checkout(&issue.branch_name)? {
+                println!("created and checked out {}", issue.branch_name);
+            } else {
+                println!("checked out {}", issue.branch_name);
+            }
+
+            let changes = requests::issue::changes::Changes {
+                state: start.then(|| "started".to_string()),
+                assignee: assign.then(|| "me".to_string()),
+                ..Default::default()
+            };
+            if !changes.is_empty() {
+                // Don't overwrite changes made since the issue was fetched for its branch name.
+                let precondition = requests::issue::update::Precondition {
+                    unmodified_since: Some(issue.updated_at),
+                    state: None,
+                };
+                let mut resolver = requests::issue::changes::Resolver::new(&client);
+                let change = update_issue(&client, &mut resolver, issue.id, &changes, &precondition).await?;
+                println!("{} updated", change.identifier);
+                journal::record(journal::Change::Update { issues: vec![change] });
+            }
+        }
+
+        Command::Team {
+            cmd: TeamCommand::List(TeamList { json, full_width }),
+        } => {
+            requests::team::list::print(
+                requests::team::list::request().client(&client).call().await,
+                json,
+                full_width,
+            );
+        }
+
+        Command::Board(Board {
+            team,
+            cycle,
+            include_empty,
+            json,
+            full_width,
+        }) => {
+            let mut resolver = requests::issue::changes::Resolver::new(&client);
+            let team_id = resolver.team(&team_or_default(team)?).await?;
+            let cycle_id = match cycle {
+                Some(cycle) => resolver.cycle(&team_id, &cycle).await?,
+                None => None,
+            };
+            requests::board::print(
+                requests::board::request()
+                    .client(&client)
+                    .team_id(team_id)
+                    .maybe_cycle_id(cycle_id)
+                    .include_empty(include_empty)
+                    .call()
+                    .await,
+                json,
+                full_width,
+            );
+        }
+
+        Command::History(History { n, json }) => {
+            journal::print(journal::load(), n, json);
+        }
+
+        Command::Undo(Undo { n, force }) => {
+            for change in journal::undo().client(&client).n(n).force(force).call().await? {
+                println!("undone: {change}");
+            }
+        }
+
+        Command::Tui(Tui {
+            sort_by,
+            filters:
+                IssueFilters {
+                    n,
+                    assignee,
+                    state,
+                    not_state,
+                    parent,
+                    blocked,
+                    include_archived,
+                },
+        }) => {
+            tui::run()
+                .client(&client)
+                .filter(tui::Filter {
+                    n,
+                    sort_by,
+                    assignee,
+                    state: combine_state_filters(state, not_state),
+                    parent,
+                    blocked,
+                    include_archived,
+                })
+                .call()
+                .await?;
+        }
+
+        Command::Hook { .. } => unreachable!("handled before the client is created"),
 
         Command::Debug {
             cmd: DebugCommand::ListWorkflowStates,
@@ -0,0 +1,92 @@
+//! Pipe long output through a pager, similar to what git does. The pager is `$PAGER` (defaulting to `less -FRX`) and is
+//! only used when stdout is a terminal. Since `less -F` exits right away if the output fits on the screen, short output
+//! is printed as if there was no pager.
+
+use std::{
+    io::{
+        IsTerminal as _,
+        Write as _,
+    },
+    process::{
+        Child,
+        Command,
+        Stdio,
+    },
+};
+
+const DEFAULT_PAGER: &str = "less -FRX";
+
+pub struct Pager {
+    child: Child,
+    #[cfg(unix)]
+    stdout: std::os::fd::OwnedFd,
+}
+
+/// Redirect stdout into a pager process. Returns `None` if stdout is not a terminal, the pager is disabled (`PAGER=` or
+/// `PAGER=cat`) or could not be started. Output goes to the terminal again once the returned guard is dropped.
+pub fn start() -> Option<Pager> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+    let pager = pager.trim();
+    if pager.is_empty() || pager == "cat" {
+        return None;
+    }
+
+    spawn(pager)
+        .inspect_err(|err| debug!(?err, %pager, "could not start pager"))
+        .ok()
+}
+
+#[cfg(unix)]
+fn spawn(pager: &str) -> std::io::Result<Pager> {
+    use std::os::fd::{
+        AsFd as _,
+        AsRawFd as _,
+        OwnedFd,
+    };
+
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(pager).stdin(Stdio::piped());
+    if std::env::var_os("LESS").is_none() {
+        cmd.env("LESS", "FRX");
+    }
+    let mut child = cmd.spawn()?;
+    let pipe = OwnedFd::from(child.stdin.take().expect("piped stdin"));
+
+    std::io::stdout().flush()?;
+    let stdout = std::io::stdout().as_fd().try_clone_to_owned()?;
+    // SAFETY: both file descriptors are valid and owned for the duration of the call.
+    if unsafe { libc::dup2(pipe.as_raw_fd(), libc::STDOUT_FILENO) } < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // If the pager is quit before all output was written we should just stop instead of failing on the broken pipe.
+    // SAFETY: resetting a signal handler to its default has no preconditions.
+    unsafe { libc::signal(libc::SIGPIPE, libc::SIG_DFL) };
+
+    Ok(Pager { child, stdout })
+}
+
+#[cfg(not(unix))]
+fn spawn(_pager: &str) -> std::io::Result<Pager> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "paging is only supported on unix",
+    ))
+}
+
+impl Drop for Pager {
+    fn drop(&mut self) {
+        let _ = std::io::stdout().flush();
+        // Restoring the original stdout closes the write end of the pipe so that the pager sees EOF.
+        #[cfg(unix)]
+        {
+            use std::os::fd::AsRawFd as _;
+            // SAFETY: both file descriptors are valid.
+            unsafe { libc::dup2(self.stdout.as_raw_fd(), libc::STDOUT_FILENO) };
+        }
+        let _ = self.child.wait();
+    }
+}
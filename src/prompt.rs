@@ -0,0 +1,50 @@
+//! Interactive prompts. They are only shown if stdin is a terminal, otherwise the caller has to pass the answer
+//! explicitly (e.g. with `--yes`) or fails.
+
+use eyre::{
+    bail,
+    Result,
+};
+use std::io::{
+    BufRead as _,
+    IsTerminal as _,
+    Write as _,
+};
+
+/// Ask a yes/no question on stderr. Defaults to "no".
+pub fn confirm(question: impl std::fmt::Display) -> Result<bool> {
+    if !std::io::stdin().is_terminal() {
+        bail!("{question} -- stdin is not a terminal, pass --yes to confirm");
+    }
+
+    eprint!("{question} [y/N] ");
+    std::io::stderr().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Whether the user can be asked to pick a value. Not if output is piped or paged, the prompt would end up in between.
+pub fn interactive() -> bool {
+    std::io::stdin().is_terminal() && std::io::stdout().is_terminal() && std::io::stderr().is_terminal()
+}
+
+/// Let the user pick one of `items` with a fuzzy finder on stderr, starting with `query` as search text. Fails if the
+/// prompt is cancelled with escape.
+pub fn pick(prompt: impl Into<String>, items: &[impl ToString], query: &str) -> Result<usize> {
+    if items.is_empty() {
+        bail!("nothing to pick from");
+    }
+
+    let colorful = dialoguer::theme::ColorfulTheme::default();
+    let simple = dialoguer::theme::SimpleTheme;
+    let theme: &dyn dialoguer::theme::Theme = if crate::style::enabled() { &colorful } else { &simple };
+    let picked = dialoguer::FuzzySelect::with_theme(theme)
+        .with_prompt(prompt)
+        .items(items)
+        .with_initial_text(query)
+        .default(0)
+        .interact_opt()?;
+    picked.ok_or_else(|| eyre::eyre!("cancelled"))
+}
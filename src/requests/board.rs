@@ -0,0 +1,172 @@
+use crate::{
+    client::Client,
+    requests::list_workflow_states,
+    style,
+};
+use eyre::Result;
+use graphql_client::{
+    GraphQLQuery,
+    QueryBody,
+};
+use serde::Serialize;
+
+// The filter is built by hand as JSON, the generated `IssueFilter` has hundreds of fields. These aliases are still
+// needed for the generated (unused) input types.
+type DateTimeOrDuration = String;
+type TimelessDateOrDuration = String;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/board.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug, Serialize"
+)]
+#[allow(dead_code)]
+struct BoardIssues;
+
+pub type Issue = board_issues::BoardIssuesIssuesNodes;
+
+/// Without a cycle, issues that were completed or canceled longer ago than this are left out, like Linear's board does.
+/// Otherwise the done column would hold every issue the team ever finished.
+const CLOSED_WITHIN: &str = "-P2W";
+
+#[derive(Debug, Serialize)]
+pub struct Column {
+    pub state: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub color: String,
+    pub issues: Vec<Issue>,
+}
+
+#[derive(Serialize)]
+struct Variables<'a> {
+    filter: &'a serde_json::Value,
+    after: Option<String>,
+}
+
+/// The issues of a team (and optionally of a cycle), with one column per workflow state of the team in the order of the
+/// board.
+#[builder]
+pub async fn request(
+    client: &Client,
+    team_id: String,
+    cycle_id: Option<String>,
+    #[builder(default)] include_empty: bool,
+) -> Result<Vec<Column>> {
+    let mut states = list_workflow_states::request()
+        .client(client)
+        .call()
+        .await?
+        .into_iter()
+        .filter(|s| s.team.id == team_id && s.archived_at.is_none())
+        .collect::<Vec<_>>();
+    states.sort_by(|a, b| a.position.total_cmp(&b.position));
+
+    let mut filter = serde_json::json!({ "team": { "id": { "eq": team_id } } });
+    match cycle_id {
+        Some(cycle_id) => filter["cycle"] = serde_json::json!({ "id": { "eq": cycle_id } }),
+        None => {
+            filter["or"] = serde_json::json!([
+                { "completedAt": { "null": true }, "canceledAt": { "null": true } },
+                { "completedAt": { "gt": CLOSED_WITHIN } },
+                { "canceledAt": { "gt": CLOSED_WITHIN } },
+            ])
+        }
+    }
+
+    let mut issues = Vec::new();
+    let mut after = None;
+    loop {
+        let query = QueryBody {
+            variables: Variables {
+                filter: &filter,
+                after,
+            },
+            query: board_issues::QUERY,
+            operation_name: board_issues::OPERATION_NAME,
+        };
+        let response = client.req::<_, board_issues::ResponseData>(query).await?.issues;
+        issues.extend(response.nodes);
+        if !response.page_info.has_next_page {
+            break;
+        }
+        after = response.page_info.end_cursor;
+    }
+
+    // Most urgent first, issues without priority last.
+    issues.sort_by_key(|i| if i.priority == 0.0 { 5 } else { i.priority as u8 });
+
+    let columns = states
+        .into_iter()
+        .map(|state| {
+            let (column, rest) = issues.drain(..).partition(|i| i.state.id == state.id);
+            issues = rest;
+            Column {
+                state: state.name,
+                type_: state.type_,
+                color: state.color,
+                issues: column,
+            }
+        })
+        .filter(|c| include_empty || !c.issues.is_empty())
+        .collect();
+    Ok(columns)
+}
+
+fn card(issue: &Issue) -> String {
+    let mut lines = vec![style::bold(style::link(&issue.identifier, &issue.url))];
+    if issue.priority != 0.0 {
+        lines[0] = format!("{} {}", lines[0], style::priority(issue.priority, &issue.priority_label));
+    }
+    lines.push(issue.title.clone());
+
+    let mut details = Vec::new();
+    if let Some(assignee) = &issue.assignee {
+        details.push(style::link(format!("@{}", assignee.display_name), &assignee.url));
+    }
+    if let Some(estimate) = issue.estimate {
+        details.push(format!("{estimate}p"));
+    }
+    details.extend(issue.labels.nodes.iter().map(|l| style::fg(&l.name, &l.color)));
+    if !details.is_empty() {
+        lines.push(details.join(" "));
+    }
+    lines.join("\n")
+}
+
+pub fn print(res: Result<Vec<Column>>, json: bool, full_width: bool) {
+    use comfy_table::*;
+
+    let res = match res {
+        Ok(res) => res,
+        Err(err) => {
+            eprintln!("{:?}", err);
+            return;
+        }
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&res).unwrap());
+        return;
+    }
+
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::UTF8_FULL_CONDENSED);
+    if full_width {
+        table.set_content_arrangement(comfy_table::ContentArrangement::Disabled);
+    } else {
+        table.set_content_arrangement(comfy_table::ContentArrangement::DynamicFullWidth);
+    }
+
+    table.set_header(
+        res.iter()
+            .map(|c| Cell::new(style::fg(format!("{} ({})", c.state, c.issues.len()), &c.color))),
+    );
+    table.add_row(res.iter().map(|c| {
+        let cards = c.issues.iter().map(card).collect::<Vec<_>>();
+        Cell::new(cards.join("\n\n"))
+    }));
+
+    println!("{table}");
+}
@@ -0,0 +1,127 @@
+use crate::client::Client;
+use eyre::{
+    bail,
+    Result,
+};
+use graphql_client::GraphQLQuery;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-archive.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct IssueArchive;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-archive.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct IssueUnarchive;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-archive.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct IssueDelete;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Archive,
+    Unarchive,
+    /// Move the issue to the trash. Trashed issues are deleted for good after 30 days unless `permanently` is set, in
+    /// which case they are deleted right away (admins only).
+    Delete {
+        permanently: bool,
+    },
+    /// Restore an issue from the trash. Linear treats trashed issues as archived, so this is the same as unarchiving.
+    Restore,
+}
+
+impl Action {
+    /// Whether the action should be confirmed before running it.
+    pub fn is_destructive(&self) -> bool {
+        matches!(self, Action::Archive | Action::Delete { .. })
+    }
+
+    /// The action that reverts this one. Permanently deleted issues are gone for good.
+    pub fn inverse(&self) -> Option<Action> {
+        match self {
+            Action::Archive => Some(Action::Unarchive),
+            Action::Unarchive => Some(Action::Archive),
+            Action::Delete { permanently: false } => Some(Action::Restore),
+            Action::Delete { permanently: true } => None,
+            Action::Restore => Some(Action::Delete { permanently: false }),
+        }
+    }
+
+    pub fn past_tense(&self) -> &'static str {
+        match self {
+            Action::Archive => "archived",
+            Action::Unarchive => "unarchived",
+            Action::Delete { permanently: false } => "moved to trash",
+            Action::Delete { permanently: true } => "deleted",
+            Action::Restore => "restored",
+        }
+    }
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Archive => write!(f, "archive"),
+            Action::Unarchive => write!(f, "unarchive"),
+            Action::Delete { permanently: false } => write!(f, "delete"),
+            Action::Delete { permanently: true } => write!(f, "permanently delete"),
+            Action::Restore => write!(f, "restore"),
+        }
+    }
+}
+
+#[builder]
+pub async fn request(client: &Client, id: String, action: Action) -> Result<()> {
+    let success = match action {
+        Action::Archive => {
+            let query = IssueArchive::build_query(issue_archive::Variables { id });
+            client
+                .req::<_, issue_archive::ResponseData>(query)
+                .await?
+                .issue_archive
+                .success
+        }
+        Action::Unarchive | Action::Restore => {
+            let query = IssueUnarchive::build_query(issue_unarchive::Variables { id });
+            client
+                .req::<_, issue_unarchive::ResponseData>(query)
+                .await?
+                .issue_unarchive
+                .success
+        }
+        Action::Delete { permanently } => {
+            let query = IssueDelete::build_query(issue_delete::Variables {
+                id,
+                permanently_delete: permanently.then_some(true),
+            });
+            client
+                .req::<_, issue_delete::ResponseData>(query)
+                .await?
+                .issue_delete
+                .success
+        }
+    };
+
+    if !success {
+        bail!("could not {action} issue");
+    }
+
+    Ok(())
+}
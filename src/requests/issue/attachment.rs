@@ -0,0 +1,239 @@
+use crate::client::Client;
+use eyre::{
+    bail,
+    OptionExt as _,
+    Result,
+};
+use graphql_client::GraphQLQuery;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+type JSONObject = serde_json::Value;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-attachment.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug, Clone, Serialize, Deserialize"
+)]
+struct IssueAttachments;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-attachment.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct AttachmentCreate;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-attachment.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct AttachmentLinkUrl;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-attachment.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct AttachmentLinkGitHubPr;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-attachment.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct AttachmentLinkGitLabMr;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-attachment.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct AttachmentDelete;
+
+pub type Issue = issue_attachments::IssueAttachmentsIssue;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+}
+
+/// The kind of link, determined from the url. GitHub pull requests and GitLab merge requests are linked through their
+/// integrations so that Linear can keep track of their status.
+enum Link {
+    GitHubPr { owner: String, repo: String, number: f64 },
+    GitLabMr { project: String, number: f64 },
+    Url,
+}
+
+impl Link {
+    fn from_url(url: &str) -> Self {
+        let Ok(parsed) = reqwest::Url::parse(url) else {
+            return Link::Url;
+        };
+        let segments = parsed
+            .path_segments()
+            .map(|s| s.filter(|s| !s.is_empty()).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        if parsed.host_str() == Some("github.com") {
+            if let [owner, repo, "pull", number, ..] = segments.as_slice() {
+                if let Ok(number) = number.parse() {
+                    return Link::GitHubPr {
+                        owner: owner.to_string(),
+                        repo: repo.to_string(),
+                        number,
+                    };
+                }
+            }
+        }
+
+        if let Some(pos) = segments.iter().position(|s| *s == "-") {
+            if let (project @ [_, ..], ["-", "merge_requests", number, ..]) = segments.split_at(pos) {
+                if let Ok(number) = number.parse() {
+                    return Link::GitLabMr {
+                        project: project.join("/"),
+                        number,
+                    };
+                }
+            }
+        }
+
+        Link::Url
+    }
+}
+
+#[builder]
+pub async fn request(client: &Client, issue_id: String) -> Result<Issue> {
+    let query = IssueAttachments::build_query(issue_attachments::Variables { issue_id });
+    client
+        .req::<_, issue_attachments::ResponseData>(query)
+        .await
+        .map(|res| res.issue)
+}
+
+/// Attach a link to an issue. A subtitle can only be set on generic attachments, in that case the link is never
+/// treated as a pull or merge request.
+#[builder]
+pub async fn create(
+    client: &Client,
+    issue_id: String,
+    url: String,
+    title: Option<String>,
+    subtitle: Option<String>,
+) -> Result<Attachment> {
+    let issue_id = request().client(client).issue_id(issue_id).call().await?.id;
+
+    let (success, attachment) = match (subtitle, Link::from_url(&url)) {
+        (Some(subtitle), _) => {
+            let query = AttachmentCreate::build_query(attachment_create::Variables {
+                input: attachment_create::AttachmentCreateInput {
+                    id: None,
+                    title: title.unwrap_or_else(|| url.clone()),
+                    subtitle: Some(subtitle),
+                    url,
+                    issue_id,
+                    icon_url: None,
+                    metadata: None,
+                    group_by_source: None,
+                    comment_body: None,
+                    comment_body_data: None,
+                    create_as_user: None,
+                },
+            });
+            let res = client
+                .req::<_, attachment_create::ResponseData>(query)
+                .await?
+                .attachment_create;
+            let attachment_create::Attachment { id, title, url } = res.attachment;
+            (res.success, Attachment { id, title, url })
+        }
+
+        (None, Link::GitHubPr { owner, repo, number }) => {
+            let query = AttachmentLinkGitHubPr::build_query(attachment_link_git_hub_pr::Variables {
+                issue_id,
+                url,
+                title,
+                owner: Some(owner),
+                repo: Some(repo),
+                number: Some(number),
+            });
+            let res = client
+                .req::<_, attachment_link_git_hub_pr::ResponseData>(query)
+                .await?
+                .attachment_link_git_hub_pr;
+            let attachment_link_git_hub_pr::Attachment { id, title, url } = res.attachment;
+            (res.success, Attachment { id, title, url })
+        }
+
+        (None, Link::GitLabMr { project, number }) => {
+            let query = AttachmentLinkGitLabMr::build_query(attachment_link_git_lab_mr::Variables {
+                issue_id,
+                url,
+                title,
+                project_path_with_namespace: project,
+                number,
+            });
+            let res = client
+                .req::<_, attachment_link_git_lab_mr::ResponseData>(query)
+                .await?
+                .attachment_link_git_lab_mr;
+            let attachment_link_git_lab_mr::Attachment { id, title, url } = res.attachment;
+            (res.success, Attachment { id, title, url })
+        }
+
+        (None, Link::Url) => {
+            let query = AttachmentLinkUrl::build_query(attachment_link_url::Variables { issue_id, url, title });
+            let res = client
+                .req::<_, attachment_link_url::ResponseData>(query)
+                .await?
+                .attachment_link_url;
+            let attachment_link_url::Attachment { id, title, url } = res.attachment;
+            (res.success, Attachment { id, title, url })
+        }
+    };
+
+    if !success {
+        bail!("could not attach {}", attachment.url);
+    }
+
+    Ok(attachment)
+}
+
+/// Remove an attachment from an issue. The attachment can be given by its id or its url.
+#[builder]
+pub async fn delete(client: &Client, issue_id: String, attachment: String) -> Result<Attachment> {
+    let issue = request().client(client).issue_id(issue_id).call().await?;
+    let issue_attachments::IssueAttachmentsIssueAttachmentsNodes { id, title, url, .. } = issue
+        .attachments
+        .nodes
+        .into_iter()
+        .find(|a| a.id == attachment || a.url == attachment)
+        .ok_or_eyre(format!("{} has no attachment {attachment}", issue.identifier))?;
+
+    delete_by_id(client, &id).await?;
+    Ok(Attachment { id, title, url })
+}
+
+pub async fn delete_by_id(client: &Client, id: &str) -> Result<()> {
+    let query = AttachmentDelete::build_query(attachment_delete::Variables { id: id.to_string() });
+    let response = client
+        .req::<_, attachment_delete::ResponseData>(query)
+        .await?
+        .attachment_delete;
+    if !response.success {
+        bail!("could not delete attachment {id}");
+    }
+    Ok(())
+}
@@ -0,0 +1,369 @@
+use super::{
+    changes::{
+        self,
+        Changes,
+        Resolver,
+        Snapshot,
+    },
+    show,
+    update,
+};
+use crate::{
+    client::{
+        Client,
+        DryRun,
+    },
+    identifier,
+    shared::IssueState,
+};
+use clap::ValueEnum as _;
+use eyre::Result;
+use graphql_client::{
+    GraphQLQuery,
+    QueryBody,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// The filter is built by hand as JSON like the one of the board, these aliases are needed for the generated (unused)
+// input types.
+type DateTimeOrDuration = String;
+type TimelessDateOrDuration = String;
+type TimelessDate = chrono::NaiveDate;
+pub type DateTime = chrono::DateTime<chrono::Utc>;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-bulk-update.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+#[allow(dead_code)]
+struct BulkUpdateTargets;
+
+pub type Issue = bulk_update_targets::BulkUpdateTargetsIssuesNodes;
+
+/// How many identifiers are looked up with a single query.
+const LOOKUP_SIZE: usize = 50;
+
+/// Select issues by their fields instead of by identifier. Unset fields match every issue.
+#[derive(Clone, Default)]
+pub struct Filter {
+    /// Display name of the assignee.
+    pub assignee: Option<String>,
+    pub state: Option<Vec<IssueState>>,
+    /// Identifier or UUID of the parent issue.
+    pub parent: Option<String>,
+    /// Only issues blocked by another issue which is not done yet.
+    pub blocked: bool,
+}
+
+impl Filter {
+    fn to_json(&self) -> serde_json::Value {
+        let mut filter = serde_json::json!({});
+        if let Some(assignee) = &self.assignee {
+            filter["assignee"] = serde_json::json!({ "displayName": { "eq": assignee } });
+        }
+        if let Some(state) = &self.state {
+            let types = state
+                .iter()
+                .filter_map(|s| s.to_possible_value())
+                .map(|v| v.get_name().to_string())
+                .collect::<Vec<_>>();
+            filter["state"] = serde_json::json!({ "type": { "in": types } });
+        }
+        if let Some(parent) = &self.parent {
+            filter["parent"] = match team_and_number(parent) {
+                Some((team, number)) => serde_json::json!({
+                    "team": { "key": { "eqIgnoreCase": team } },
+                    "number": { "eq": number },
+                }),
+                None => serde_json::json!({ "id": { "eq": parent } }),
+            };
+        }
+        if self.blocked {
+            filter["hasBlockedByRelations"] = serde_json::json!({ "eq": true });
+        }
+        filter
+    }
+}
+
+/// Split an identifier like `ENG-123` into the team key and the number.
+fn team_and_number(identifier: &str) -> Option<(&str, u32)> {
+    let (team, number) = identifier.rsplit_once('-')?;
+    Some((team, number.parse().ok()?))
+}
+
+/// A filter matching the issues given by identifier or UUID.
+fn ids_filter(ids: &[String]) -> serde_json::Value {
+    let mut uuids = Vec::new();
+    let mut numbers = BTreeMap::<String, Vec<u32>>::new();
+    for id in ids {
+        match team_and_number(id) {
+            Some((team, number)) if !identifier::is_uuid(id) => {
+                numbers.entry(team.to_uppercase()).or_default().push(number)
+            }
+            _ => uuids.push(id.clone()),
+        }
+    }
+
+    let mut or = Vec::new();
+    if !uuids.is_empty() {
+        or.push(serde_json::json!({ "id": { "in": uuids } }));
+    }
+    for (team, numbers) in numbers {
+        or.push(serde_json::json!({
+            "team": { "key": { "eqIgnoreCase": team } },
+            "number": { "in": numbers },
+        }));
+    }
+    serde_json::json!({ "or": or })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Variables<'a> {
+    filter: &'a serde_json::Value,
+    include_archived: bool,
+    after: Option<String>,
+}
+
+async fn query(client: &Client, filter: &serde_json::Value, include_archived: bool) -> Result<Vec<Issue>> {
+    let mut issues = Vec::new();
+    let mut after = None;
+    loop {
+        let query = QueryBody {
+            variables: Variables {
+                filter,
+                include_archived,
+                after,
+            },
+            query: bulk_update_targets::QUERY,
+            operation_name: bulk_update_targets::OPERATION_NAME,
+        };
+        let response = client
+            .req::<_, bulk_update_targets::ResponseData>(query)
+            .await?
+            .issues;
+        issues.extend(response.nodes);
+        if !response.page_info.has_next_page {
+            break;
+        }
+        after = response.page_info.end_cursor;
+    }
+    Ok(issues)
+}
+
+/// An issue to update, with the fields needed to resolve changes and to batch them.
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub id: String,
+    pub identifier: String,
+    pub team_id: String,
+    pub before: Snapshot,
+}
+
+impl From<&Issue> for Target {
+    fn from(issue: &Issue) -> Self {
+        Self {
+            id: issue.id.clone(),
+            identifier: issue.identifier.clone(),
+            team_id: issue.team.id.clone(),
+            before: issue.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Outcome {
+    pub identifier: String,
+    pub updated: bool,
+    pub error: Option<String>,
+    /// The id and the values before and after the update, to record it in the journal.
+    #[serde(skip)]
+    pub id: String,
+    #[serde(skip)]
+    pub before: update::Input,
+    #[serde(skip)]
+    pub after: update::Input,
+    #[serde(skip)]
+    pub updated_at: Option<DateTime>,
+}
+
+impl Outcome {
+    fn failed(identifier: String, error: String) -> Self {
+        Self {
+            identifier,
+            updated: false,
+            error: Some(error),
+            id: String::new(),
+            before: update::Input::default(),
+            after: update::Input::default(),
+            updated_at: None,
+        }
+    }
+}
+
+/// Look up issues by identifier or UUID, [`LOOKUP_SIZE`] at a time, archived ones included. Issues that can't be found
+/// are left out.
+pub async fn lookup(client: &Client, ids: &[String]) -> Result<Vec<Issue>> {
+    let mut issues = Vec::new();
+    for chunk in ids.chunks(LOOKUP_SIZE) {
+        issues.extend(query(client, &ids_filter(chunk), true).await?);
+    }
+    Ok(issues)
+}
+
+/// Look up the issues to update: the ones given by identifier or UUID, fetched [`LOOKUP_SIZE`] at a time, and the ones
+/// matching `filter`. Issues that can't be found are returned as failed outcomes, so that they show up in the report.
+#[builder]
+pub async fn targets(client: &Client, ids: Vec<String>, filter: Option<Filter>) -> Result<(Vec<Target>, Vec<Outcome>)> {
+    let mut targets = Vec::<Target>::new();
+    let mut failed = Vec::new();
+
+    if let Some(filter) = filter {
+        let issues = query(client, &filter.to_json(), false).await?;
+        targets.extend(
+            issues
+                .iter()
+                // The API only knows whether an issue has blocking issues, not whether they are done yet.
+                .filter(|i| {
+                    !filter.blocked
+                        || i.inverse_relations.nodes.iter().any(|r| {
+                            r.type_ == "blocks" && !matches!(r.issue.state.type_.as_str(), "completed" | "canceled")
+                        })
+                })
+                .map(Target::from),
+        );
+    }
+
+    let issues = lookup(client, &ids).await?;
+    for id in &ids {
+        let found = issues.iter().find(|i| i.id == *id || i.identifier.eq_ignore_ascii_case(id));
+        let target = match found {
+            Some(issue) => Target::from(issue),
+            // Issues that moved to another team are still found under their old identifier, just not by a filter.
+            None => match show::request().client(client).issue_id(id.clone()).call().await {
+                Ok(issue) => Target {
+                    id: issue.id.clone(),
+                    identifier: issue.identifier.clone(),
+                    team_id: issue.team.id.clone(),
+                    before: (&issue).into(),
+                },
+                Err(err) => {
+                    debug!(%id, ?err, "issue not found");
+                    failed.push(Outcome::failed(id.clone(), "issue not found".to_string()));
+                    continue;
+                }
+            },
+        };
+        if !targets.iter().any(|t| t.id == target.id) {
+            targets.push(target);
+        }
+    }
+
+    Ok((targets, failed))
+}
+
+/// Apply `changes` to all `targets`. Issues are grouped by team (states, labels and cycles differ between teams) and
+/// updated in chunks of [`update::BATCH_SIZE`]. A failing chunk, or a team that e.g. has no state of the given name,
+/// does not stop the remaining ones.
+///
+/// With `--dry-run` the changes to every issue and the mutation of every chunk are printed, only failures are returned.
+#[builder]
+pub async fn request(client: &Client, targets: Vec<Target>, changes: &Changes) -> Result<Vec<Outcome>> {
+    let mut by_team = BTreeMap::<String, Vec<Target>>::new();
+    for target in targets {
+        by_team.entry(target.team_id.clone()).or_default().push(target);
+    }
+
+    let mut resolver = Resolver::new(client);
+    let mut outcomes = Vec::new();
+
+    for (team_id, targets) in by_team {
+        // A state or label that doesn't exist in one team shouldn't keep the issues of the other teams from updating.
+        let input = match resolver.resolve(&team_id, changes).await {
+            Ok(input) => input,
+            Err(err) => {
+                let error = format!("{err:#}");
+                outcomes.extend(targets.iter().map(|t| Outcome::failed(t.identifier.clone(), error.clone())));
+                continue;
+            }
+        };
+
+        if client.is_dry_run() {
+            for target in &targets {
+                let diff = resolver.diff(&target.before, &input).await?;
+                changes::print_diff(&target.identifier, &target.before.title, &diff);
+            }
+        }
+
+        for chunk in targets.chunks(update::BATCH_SIZE) {
+            let ids = chunk.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+            debug!(team = %team_id, n = ids.len(), "batch update");
+
+            match update::batch().client(client).ids(ids).input(&input).call().await {
+                Ok(updated) => outcomes.extend(chunk.iter().map(|t| {
+                    let updated = updated.iter().find(|i| i.id == t.id);
+                    Outcome {
+                        identifier: t.identifier.clone(),
+                        updated: updated.is_some(),
+                        error: updated.is_none().then(|| "not updated".to_string()),
+                        id: t.id.clone(),
+                        before: t.before.revert(&input),
+                        after: input.clone(),
+                        updated_at: updated.map(|i| i.updated_at),
+                    }
+                })),
+                Err(err) if err.is::<DryRun>() => {}
+                Err(err) => outcomes.extend(
+                    chunk
+                        .iter()
+                        .map(|t| Outcome::failed(t.identifier.clone(), err.to_string())),
+                ),
+            }
+        }
+    }
+
+    Ok(outcomes)
+}
+
+pub fn print(res: Result<Vec<Outcome>>, json: bool) {
+    use comfy_table::*;
+
+    let res = match res {
+        Ok(res) => res,
+        Err(err) => {
+            eprintln!("{:?}", err);
+            return;
+        }
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&res).unwrap());
+        return;
+    }
+
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::NOTHING);
+    table.set_content_arrangement(comfy_table::ContentArrangement::DynamicFullWidth);
+
+    let failed = res.iter().filter(|o| !o.updated).count();
+    for Outcome {
+        identifier,
+        updated,
+        error,
+        ..
+    } in res.iter()
+    {
+        let status = if *updated {
+            "updated".to_string()
+        } else {
+            format!("FAILED: {}", error.as_deref().unwrap_or_default())
+        };
+        table.add_row([Cell::new(identifier), Cell::new(status)]);
+    }
+
+    println!("{table}");
+    println!("{} updated, {failed} failed", res.len() - failed);
+}
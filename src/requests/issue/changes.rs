@@ -0,0 +1,633 @@
+use super::{
+    bulk_update,
+    show,
+    update,
+};
+use crate::{
+    client::Client,
+    prompt,
+    requests::{
+        list_cycles::{
+            self,
+            Cycle,
+        },
+        list_labels::{
+            self,
+            Label,
+        },
+        list_projects::{
+            self,
+            Project,
+        },
+        list_users::{
+            self,
+            User,
+        },
+        list_workflow_states::{
+            self,
+            WorkflowState,
+        },
+        me,
+        team::list::{
+            self as list_teams,
+            Team,
+        },
+    },
+    shared::Priority,
+};
+use chrono::NaiveDate;
+use eyre::{
+    bail,
+    Result,
+};
+use std::collections::HashMap;
+
+/// Value that clears an optional field, e.g. `--assignee none`.
+const NONE: &str = "none";
+
+/// Changes to an issue as given on the command line, i.e. with names instead of ids.
+#[derive(Debug, Clone, Default)]
+pub struct Changes {
+    pub title: Option<String>,
+    /// Markdown, replacing the whole description.
+    pub description: Option<String>,
+    /// Name of a workflow state, or a state type like `started` which picks the first state of that type. Empty to let
+    /// the user pick one.
+    pub state: Option<String>,
+    /// Display name, name or email of a user, `me` or `none`. Empty to let the user pick one.
+    pub assignee: Option<String>,
+    /// Replaces all labels. An empty list or `none` removes all labels.
+    pub labels: Option<Vec<String>>,
+    pub priority: Option<Priority>,
+    /// Cycle number, name, `current`, `next`, `previous` or `none`.
+    pub cycle: Option<String>,
+    /// Project name or `none`.
+    pub project: Option<String>,
+    /// `Some(None)` removes the estimate.
+    pub estimate: Option<Option<i64>>,
+    /// `Some(None)` removes the due date.
+    pub due_date: Option<Option<NaiveDate>>,
+}
+
+impl Changes {
+    pub fn is_empty(&self) -> bool {
+        let Changes {
+            title,
+            description,
+            state,
+            assignee,
+            labels,
+            priority,
+            cycle,
+            project,
+            estimate,
+            due_date,
+        } = self;
+        title.is_none()
+            && description.is_none()
+            && state.is_none()
+            && assignee.is_none()
+            && labels.is_none()
+            && priority.is_none()
+            && cycle.is_none()
+            && project.is_none()
+            && estimate.is_none()
+            && due_date.is_none()
+    }
+}
+
+/// The current values of the fields that [`Changes`] can touch, used to show what an update would do.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub title: String,
+    pub state: String,
+    pub assignee: Option<String>,
+    pub labels: Vec<String>,
+    pub priority: String,
+    pub cycle: Option<String>,
+    pub project: Option<String>,
+    pub estimate: Option<String>,
+    pub due_date: Option<String>,
+    /// The same values as ids, with every field that is known set.
+    pub values: update::Input,
+}
+
+impl Snapshot {
+    /// The input that restores the current values of the fields that `input` changes.
+    pub fn revert(&self, input: &update::Input) -> update::Input {
+        let values = &self.values;
+        update::Input {
+            title: input.title.as_ref().and(values.title.clone()),
+            description: input.description.as_ref().and(values.description.clone()),
+            state_id: input.state_id.as_ref().and(values.state_id.clone()),
+            assignee_id: input.assignee_id.as_ref().and(values.assignee_id.clone()),
+            priority: input.priority.and(values.priority),
+            label_ids: input.label_ids.as_ref().and(values.label_ids.clone()),
+            cycle_id: input.cycle_id.as_ref().and(values.cycle_id.clone()),
+            project_id: input.project_id.as_ref().and(values.project_id.clone()),
+            estimate: input.estimate.and(values.estimate),
+            due_date: input.due_date.and(values.due_date),
+        }
+    }
+}
+
+impl From<&show::Issue> for Snapshot {
+    fn from(issue: &show::Issue) -> Self {
+        Self {
+            title: issue.title.clone(),
+            state: issue.state.name.clone(),
+            assignee: issue.assignee.as_ref().map(|a| a.display_name.clone()),
+            labels: issue.labels.nodes.iter().map(|l| l.name.clone()).collect(),
+            priority: issue.priority_label.clone(),
+            cycle: issue.cycle.as_ref().map(|c| cycle_label(c.number, c.name.as_deref())),
+            project: issue.project.as_ref().map(|p| p.name.clone()),
+            estimate: issue.estimate.map(|e| e.to_string()),
+            due_date: issue.due_date.map(|d| d.to_string()),
+            values: update::Input {
+                title: Some(issue.title.clone()),
+                // An issue without description has `null`, which can't be sent as `IssueUpdateInput.description`.
+                description: Some(issue.description.clone().unwrap_or_default()),
+                state_id: Some(issue.state.id.clone()),
+                assignee_id: Some(issue.assignee.as_ref().map(|a| a.id.clone())),
+                priority: Some(issue.priority as i64),
+                label_ids: Some(issue.labels.nodes.iter().map(|l| l.id.clone()).collect()),
+                cycle_id: Some(issue.cycle.as_ref().map(|c| c.id.clone())),
+                project_id: Some(issue.project.as_ref().map(|p| p.id.clone())),
+                estimate: Some(issue.estimate.map(|e| e as i64)),
+                due_date: Some(issue.due_date),
+            },
+        }
+    }
+}
+
+impl From<&bulk_update::Issue> for Snapshot {
+    fn from(issue: &bulk_update::Issue) -> Self {
+        Self {
+            title: issue.title.clone(),
+            state: issue.state.name.clone(),
+            assignee: issue.assignee.as_ref().map(|a| a.display_name.clone()),
+            labels: issue.labels.nodes.iter().map(|l| l.name.clone()).collect(),
+            priority: issue.priority_label.clone(),
+            cycle: issue.cycle.as_ref().map(|c| cycle_label(c.number, c.name.as_deref())),
+            project: issue.project.as_ref().map(|p| p.name.clone()),
+            estimate: issue.estimate.map(|e| e.to_string()),
+            due_date: issue.due_date.map(|d| d.to_string()),
+            values: update::Input {
+                title: Some(issue.title.clone()),
+                description: Some(issue.description.clone().unwrap_or_default()),
+                state_id: Some(issue.state.id.clone()),
+                assignee_id: Some(issue.assignee.as_ref().map(|a| a.id.clone())),
+                priority: Some(issue.priority as i64),
+                label_ids: Some(issue.labels.nodes.iter().map(|l| l.id.clone()).collect()),
+                cycle_id: Some(issue.cycle.as_ref().map(|c| c.id.clone())),
+                project_id: Some(issue.project.as_ref().map(|p| p.id.clone())),
+                estimate: Some(issue.estimate.map(|e| e as i64)),
+                due_date: Some(issue.due_date),
+            },
+        }
+    }
+}
+
+/// A field that an update changes, with old and new value as they are shown to the user.
+#[derive(Debug, Clone)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+/// Print the changes to a single issue, one field per line.
+pub fn print_diff(identifier: &str, title: &str, diff: &[FieldChange]) {
+    println!("{identifier} {title}");
+    if diff.is_empty() {
+        println!("  no changes");
+    }
+    for FieldChange { field, old, new } in diff {
+        println!("  {field:<10} {old} -> {new}");
+    }
+}
+
+/// The first line of a description, shortened to fit into a diff.
+fn summary(description: &str) -> String {
+    const MAX: usize = 40;
+
+    let first_line = description.lines().find(|l| !l.trim().is_empty()).unwrap_or_default().trim();
+    if first_line.is_empty() {
+        NONE.to_string()
+    } else if first_line.chars().count() > MAX || description.trim().lines().nth(1).is_some() {
+        format!("{}…", first_line.chars().take(MAX).collect::<String>())
+    } else {
+        first_line.to_string()
+    }
+}
+
+pub fn cycle_label(number: f64, name: Option<&str>) -> String {
+    match name {
+        Some(name) => name.to_string(),
+        None => format!("Cycle {number}"),
+    }
+}
+
+/// Same labels as Linear's `priorityLabel`.
+pub fn priority_label(priority: i64) -> &'static str {
+    match priority {
+        1 => "Urgent",
+        2 => "High",
+        3 => "Medium",
+        4 => "Low",
+        _ => "No priority",
+    }
+}
+
+/// Translates names into ids. Lookups are fetched lazily and cached, so resolving the same changes for many issues only
+/// costs a few requests.
+pub struct Resolver<'a> {
+    client: &'a Client,
+    me: Option<String>,
+    states: Option<Vec<WorkflowState>>,
+    users: Option<Vec<User>>,
+    labels: Option<Vec<Label>>,
+    projects: Option<Vec<Project>>,
+    teams: Option<Vec<Team>>,
+    cycles: HashMap<String, Vec<Cycle>>,
+    picked: HashMap<PickKey, String>,
+}
+
+/// What was picked (`state`, `user`, ...), the team it was picked for and the value that was missing or unknown.
+type PickKey = (&'static str, String, String);
+
+/// Let the user pick one of `options` (id and label) in place of a value that is missing or unknown, or fail with `err`
+/// if there is nobody to ask. Picks are remembered, so that e.g. `issue bulk-update` asks only once per team.
+fn pick(
+    picked: &mut HashMap<PickKey, String>,
+    key: PickKey,
+    options: &[(&str, String)],
+    err: eyre::Report,
+) -> Result<String> {
+    if let Some(id) = picked.get(&key) {
+        return Ok(id.clone());
+    }
+    if !prompt::interactive() {
+        return Err(err);
+    }
+
+    let labels = options.iter().map(|(_, label)| label).collect::<Vec<_>>();
+    let i = prompt::pick(format!("Pick a {}", key.0), &labels, &key.2)?;
+    let id = options[i].0.to_string();
+    picked.insert(key, id.clone());
+    Ok(id)
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(client: &'a Client) -> Self {
+        Self {
+            client,
+            me: None,
+            states: None,
+            users: None,
+            labels: None,
+            projects: None,
+            teams: None,
+            cycles: HashMap::new(),
+            picked: HashMap::new(),
+        }
+    }
+
+    /// Resolve `changes` for an issue of the team `team_id`. States, labels and cycles are specific to a team.
+    pub async fn resolve(&mut self, team_id: &str, changes: &Changes) -> Result<update::Input> {
+        let Changes {
+            title,
+            description,
+            state,
+            assignee,
+            labels,
+            priority,
+            cycle,
+            project,
+            estimate,
+            due_date,
+        } = changes;
+
+        let mut input = update::Input {
+            title: title.clone(),
+            description: description.clone(),
+            priority: priority.map(|p| p as i64),
+            estimate: *estimate,
+            due_date: *due_date,
+            ..Default::default()
+        };
+
+        if let Some(state) = state {
+            input.state_id = Some(self.state(team_id, state).await?.id.clone());
+        }
+        if let Some(assignee) = assignee {
+            input.assignee_id = Some(self.user(assignee).await?);
+        }
+        if let Some(labels) = labels {
+            input.label_ids = Some(self.labels(team_id, labels).await?);
+        }
+        if let Some(cycle) = cycle {
+            input.cycle_id = Some(self.cycle(team_id, cycle).await?);
+        }
+        if let Some(project) = project {
+            input.project_id = Some(self.project(project).await?);
+        }
+
+        Ok(input)
+    }
+
+    /// Compare the current values of an issue with what `input` would set them to. Unchanged fields are left out. Ids
+    /// in `input` are translated back to names, fetching the lists of users etc. if they haven't been fetched yet.
+    pub async fn diff(&mut self, before: &Snapshot, input: &update::Input) -> Result<Vec<FieldChange>> {
+        fn or_none(value: Option<&str>) -> String {
+            value.unwrap_or(NONE).to_string()
+        }
+
+        let mut diff = Vec::new();
+        let mut push = |field, old: String, new: String| {
+            if old != new {
+                diff.push(FieldChange { field, old, new });
+            }
+        };
+
+        if let Some(title) = &input.title {
+            push("title", before.title.clone(), title.clone());
+        }
+        if let Some(description) = &input.description {
+            let old = before.values.description.as_deref().unwrap_or_default();
+            if old != description {
+                push("description", summary(old), summary(description));
+            }
+        }
+        if let Some(state_id) = &input.state_id {
+            self.ensure_states().await?;
+            let name = self.states.iter().flatten().find(|s| &s.id == state_id).map(|s| s.name.clone());
+            push("state", before.state.clone(), name.unwrap_or_else(|| state_id.clone()));
+        }
+        if let Some(assignee_id) = &input.assignee_id {
+            self.ensure_users().await?;
+            let name = assignee_id.as_ref().map(|id| {
+                self.users
+                    .iter()
+                    .flatten()
+                    .find(|u| &u.id == id)
+                    .map_or_else(|| id.clone(), |u| u.display_name.clone())
+            });
+            push("assignee", or_none(before.assignee.as_deref()), or_none(name.as_deref()));
+        }
+        if let Some(priority) = input.priority {
+            push("priority", before.priority.clone(), priority_label(priority).to_string());
+        }
+        if let Some(label_ids) = &input.label_ids {
+            let names = if label_ids.is_empty() {
+                Vec::new()
+            } else {
+                self.ensure_labels().await?;
+                label_ids
+                    .iter()
+                    .map(|id| {
+                        self.labels
+                            .iter()
+                            .flatten()
+                            .find(|l| &l.id == id)
+                            .map_or_else(|| id.clone(), |l| l.name.clone())
+                    })
+                    .collect()
+            };
+            let fmt = |labels: &[String]| {
+                if labels.is_empty() {
+                    NONE.to_string()
+                } else {
+                    labels.join(", ")
+                }
+            };
+            push("labels", fmt(&before.labels), fmt(&names));
+        }
+        if let Some(cycle_id) = &input.cycle_id {
+            // The cycle was resolved for the team of the issue, so it is in the cache.
+            let name = cycle_id.as_ref().map(|id| {
+                self.cycles
+                    .values()
+                    .flatten()
+                    .find(|c| &c.id == id)
+                    .map_or_else(|| id.clone(), |c| cycle_label(c.number, c.name.as_deref()))
+            });
+            push("cycle", or_none(before.cycle.as_deref()), or_none(name.as_deref()));
+        }
+        if let Some(project_id) = &input.project_id {
+            self.ensure_projects().await?;
+            let name = project_id.as_ref().map(|id| {
+                self.projects
+                    .iter()
+                    .flatten()
+                    .find(|p| &p.id == id)
+                    .map_or_else(|| id.clone(), |p| p.name.clone())
+            });
+            push("project", or_none(before.project.as_deref()), or_none(name.as_deref()));
+        }
+        if let Some(estimate) = input.estimate {
+            let new = estimate.map(|e| e.to_string());
+            push("estimate", or_none(before.estimate.as_deref()), or_none(new.as_deref()));
+        }
+        if let Some(due_date) = input.due_date {
+            let new = due_date.map(|d| d.to_string());
+            push("due date", or_none(before.due_date.as_deref()), or_none(new.as_deref()));
+        }
+
+        Ok(diff)
+    }
+
+    async fn ensure_states(&mut self) -> Result<()> {
+        if self.states.is_none() {
+            self.states = Some(list_workflow_states::request().client(self.client).call().await?);
+        }
+        Ok(())
+    }
+
+    async fn ensure_users(&mut self) -> Result<()> {
+        if self.users.is_none() {
+            self.users = Some(list_users::request().client(self.client).call().await?);
+        }
+        Ok(())
+    }
+
+    async fn ensure_labels(&mut self) -> Result<()> {
+        if self.labels.is_none() {
+            self.labels = Some(list_labels::request().client(self.client).call().await?);
+        }
+        Ok(())
+    }
+
+    async fn ensure_projects(&mut self) -> Result<()> {
+        if self.projects.is_none() {
+            self.projects = Some(list_projects::request().client(self.client).call().await?);
+        }
+        Ok(())
+    }
+
+    pub async fn state(&mut self, team_id: &str, name: &str) -> Result<&WorkflowState> {
+        self.ensure_states().await?;
+        let mut states = self
+            .states
+            .iter()
+            .flatten()
+            .filter(|s| s.team.id == team_id && s.archived_at.is_none())
+            .collect::<Vec<_>>();
+        states.sort_by(|a, b| a.position.total_cmp(&b.position));
+
+        let found = states
+            .iter()
+            .find(|s| s.name.eq_ignore_ascii_case(name))
+            .or_else(|| states.iter().find(|s| s.type_.eq_ignore_ascii_case(name)))
+            .copied();
+        if let Some(state) = found {
+            return Ok(state);
+        }
+
+        let names = states.iter().map(|s| s.name.as_str()).collect::<Vec<_>>().join(", ");
+        let err = if name.is_empty() {
+            eyre::eyre!("no state given, expected one of: {names}")
+        } else {
+            eyre::eyre!("unknown state {name:?}, expected one of: {names}")
+        };
+        let options = states.iter().map(|s| (s.id.as_str(), s.name.clone())).collect::<Vec<_>>();
+        let id = pick(&mut self.picked, ("state", team_id.to_string(), name.to_string()), &options, err)?;
+        Ok(states.into_iter().find(|s| s.id == id).expect("picked one of the states"))
+    }
+
+    /// Returns `None` for `none`, i.e. to unassign.
+    pub async fn user(&mut self, name: &str) -> Result<Option<String>> {
+        if name.eq_ignore_ascii_case(NONE) {
+            return Ok(None);
+        }
+        if name.eq_ignore_ascii_case("me") {
+            if self.me.is_none() {
+                self.me = Some(me::request(self.client).await?.id);
+            }
+            return Ok(self.me.clone());
+        }
+
+        self.ensure_users().await?;
+        let users = self.users.iter().flatten().filter(|u| u.active).collect::<Vec<_>>();
+        let user = users.iter().find(|u| {
+            u.display_name.eq_ignore_ascii_case(name)
+                || u.name.eq_ignore_ascii_case(name)
+                || u.email.eq_ignore_ascii_case(name)
+        });
+        if let Some(user) = user {
+            return Ok(Some(user.id.clone()));
+        }
+
+        let err = if name.is_empty() {
+            eyre::eyre!("no user given")
+        } else {
+            eyre::eyre!("unknown user {name:?}")
+        };
+        let options = users
+            .iter()
+            .map(|u| (u.id.as_str(), format!("{} ({}, {})", u.display_name, u.name, u.email)))
+            .collect::<Vec<_>>();
+        pick(&mut self.picked, ("user", String::new(), name.to_string()), &options, err).map(Some)
+    }
+
+    pub async fn labels(&mut self, team_id: &str, names: &[String]) -> Result<Vec<String>> {
+        if names.is_empty() || (names.len() == 1 && names[0].eq_ignore_ascii_case(NONE)) {
+            return Ok(Vec::new());
+        }
+
+        self.ensure_labels().await?;
+        // Workspace labels have no team and can be used on issues of every team.
+        let available = self
+            .labels
+            .iter()
+            .flatten()
+            .filter(|l| !l.is_group && l.team.as_ref().is_none_or(|t| t.id == team_id))
+            .collect::<Vec<_>>();
+
+        let options = available.iter().map(|l| (l.id.as_str(), l.name.clone())).collect::<Vec<_>>();
+        names
+            .iter()
+            .map(|name| match available.iter().find(|l| l.name.eq_ignore_ascii_case(name)) {
+                Some(label) => Ok(label.id.clone()),
+                None => {
+                    let key = ("label", team_id.to_string(), name.clone());
+                    pick(&mut self.picked, key, &options, eyre::eyre!("unknown label {name:?}"))
+                }
+            })
+            .collect()
+    }
+
+    /// Returns `None` for `none`, i.e. to remove the issue from its cycle.
+    pub async fn cycle(&mut self, team_id: &str, name: &str) -> Result<Option<String>> {
+        if name.eq_ignore_ascii_case(NONE) {
+            return Ok(None);
+        }
+
+        if !self.cycles.contains_key(team_id) {
+            let cycles = list_cycles::request()
+                .client(self.client)
+                .team_id(team_id.to_string())
+                .call()
+                .await?;
+            self.cycles.insert(team_id.to_string(), cycles);
+        }
+        let cycles = &self.cycles[team_id];
+        let now = chrono::Utc::now();
+
+        let cycle = match name.to_lowercase().as_str() {
+            "current" => cycles.iter().find(|c| c.starts_at <= now && now < c.ends_at),
+            "next" => cycles.iter().filter(|c| c.starts_at > now).min_by_key(|c| c.starts_at),
+            "previous" => cycles.iter().filter(|c| c.ends_at <= now).max_by_key(|c| c.ends_at),
+            _ => cycles.iter().find(|c| {
+                c.id == name
+                    || name.parse::<f64>().is_ok_and(|n| n == c.number)
+                    || c.name.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(name))
+            }),
+        };
+        match cycle {
+            Some(cycle) => Ok(Some(cycle.id.clone())),
+            None => bail!("unknown cycle {name:?}"),
+        }
+    }
+
+    /// Returns the id of the team with the given key, name or id. Empty to let the user pick one.
+    pub async fn team(&mut self, name: &str) -> Result<String> {
+        if self.teams.is_none() {
+            self.teams = Some(list_teams::request().client(self.client).call().await?);
+        }
+        let teams = self.teams.iter().flatten().collect::<Vec<_>>();
+        let team = teams
+            .iter()
+            .find(|t| t.id == name || t.key.eq_ignore_ascii_case(name) || t.name.eq_ignore_ascii_case(name));
+        if let Some(team) = team {
+            return Ok(team.id.clone());
+        }
+
+        let keys = teams.iter().map(|t| t.key.as_str()).collect::<Vec<_>>().join(", ");
+        let err = if name.is_empty() {
+            eyre::eyre!("no team given, expected one of: {keys}")
+        } else {
+            eyre::eyre!("unknown team {name:?}, expected one of: {keys}")
+        };
+        let options = teams.iter().map(|t| (t.id.as_str(), format!("{} {}", t.key, t.name))).collect::<Vec<_>>();
+        pick(&mut self.picked, ("team", String::new(), name.to_string()), &options, err)
+    }
+
+    /// Returns `None` for `none`, i.e. to remove the issue from its project.
+    pub async fn project(&mut self, name: &str) -> Result<Option<String>> {
+        if name.eq_ignore_ascii_case(NONE) {
+            return Ok(None);
+        }
+
+        self.ensure_projects().await?;
+        let project = self
+            .projects
+            .iter()
+            .flatten()
+            .find(|p| p.id == name || p.name.eq_ignore_ascii_case(name));
+        match project {
+            Some(project) => Ok(Some(project.id.clone())),
+            None => bail!("unknown project {name:?}"),
+        }
+    }
+}
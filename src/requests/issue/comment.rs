@@ -0,0 +1,50 @@
+use crate::client::Client;
+use eyre::{
+    bail,
+    Result,
+};
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/comment-create.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct CommentCreate;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/comment-delete.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct CommentDelete;
+
+pub type Comment = comment_create::CommentCreateCommentCreateComment;
+
+#[builder]
+pub async fn create(client: &Client, issue_id: String, body: String) -> Result<Comment> {
+    let query = CommentCreate::build_query(comment_create::Variables { issue_id, body });
+    let response = client
+        .req::<_, comment_create::ResponseData>(query)
+        .await?
+        .comment_create;
+    if !response.success {
+        bail!("could not create comment");
+    }
+    Ok(response.comment)
+}
+
+#[builder]
+pub async fn delete(client: &Client, id: String) -> Result<()> {
+    let query = CommentDelete::build_query(comment_delete::Variables { id: id.clone() });
+    let response = client
+        .req::<_, comment_delete::ResponseData>(query)
+        .await?
+        .comment_delete;
+    if !response.success {
+        bail!("could not delete comment {id}");
+    }
+    Ok(())
+}
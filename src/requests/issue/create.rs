@@ -0,0 +1,66 @@
+use super::update;
+use crate::client::Client;
+use eyre::{
+    bail,
+    Result,
+};
+use graphql_client::{
+    GraphQLQuery,
+    QueryBody,
+};
+use serde::Serialize;
+
+// The variables are built by hand from an [`update::Input`], like the ones of `issueUpdate`.
+#[allow(clippy::upper_case_acronyms)]
+type JSON = serde_json::Value;
+type DateTime = chrono::DateTime<chrono::Utc>;
+type TimelessDate = chrono::NaiveDate;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-create.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+#[allow(dead_code)]
+struct IssueCreate;
+
+pub type Issue = issue_create::IssueCreateIssueCreateIssue;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateInput<'a> {
+    team_id: String,
+    #[serde(flatten)]
+    input: &'a update::Input,
+}
+
+#[derive(Serialize)]
+struct CreateVariables<'a> {
+    input: CreateInput<'a>,
+}
+
+/// Create an issue in the team `team_id` with the fields set in `input`. Linear picks the default state of the team if
+/// `input` has none.
+#[builder]
+pub async fn request(client: &Client, team_id: String, input: &update::Input) -> Result<Issue> {
+    if input.title.as_deref().is_none_or(|t| t.trim().is_empty()) {
+        bail!("an issue needs a title");
+    }
+
+    let query = QueryBody {
+        variables: CreateVariables {
+            input: CreateInput { team_id, input },
+        },
+        query: issue_create::QUERY,
+        operation_name: issue_create::OPERATION_NAME,
+    };
+    let response = client
+        .req::<_, issue_create::ResponseData>(query)
+        .await?
+        .issue_create;
+    match response.issue {
+        Some(issue) if response.success => Ok(issue),
+        _ => bail!("could not create issue"),
+    }
+}
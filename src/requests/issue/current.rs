@@ -0,0 +1,125 @@
+use crate::{
+    client::{
+        self,
+        Client,
+    },
+    git,
+    identifier,
+    prompt,
+};
+use eyre::{
+    bail,
+    OptionExt as _,
+    Result,
+};
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-current.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct IssueIdentifier;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-current.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct IssueVcsBranchSearch;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-current.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct AssignedIssues;
+
+/// Find the issue that belongs to the checked out git branch and return its identifier. The identifier is taken from
+/// the branch name if it contains one, otherwise Linear is asked which issue the branch is linked to.
+#[builder]
+pub async fn request(client: &Client) -> Result<String> {
+    let branch = git::current_branch()?.ok_or_eyre("no issue given and HEAD is detached")?;
+    find(client, &branch)
+        .await?
+        .ok_or_else(|| eyre::eyre!("no issue given and none found for branch {branch}"))
+}
+
+/// The identifier of the issue that belongs to `branch`, or `None` if there is none. Failing requests are errors.
+async fn find(client: &Client, branch: &str) -> Result<Option<String>> {
+    if let Some(candidate) = identifier::from_branch(branch) {
+        // Branch names like `fix/utf-8-handling` look like identifiers too, check that the issue exists.
+        let query = IssueIdentifier::build_query(issue_identifier::Variables { id: candidate.clone() });
+        match client.req::<_, issue_identifier::ResponseData>(query).await {
+            Ok(res) => return Ok(Some(res.issue.identifier)),
+            Err(err) if err.is::<client::NotFound>() => {
+                debug!(%candidate, ?err, "no issue for identifier in branch name")
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    let query = IssueVcsBranchSearch::build_query(issue_vcs_branch_search::Variables {
+        branch_name: branch.to_string(),
+    });
+    Ok(client
+        .req::<_, issue_vcs_branch_search::ResponseData>(query)
+        .await?
+        .issue_vcs_branch_search
+        .map(|issue| issue.identifier))
+}
+
+/// Let the user pick one of the issues assigned to them that are not done yet, most recently updated first.
+async fn pick_assigned(client: &Client) -> Result<String> {
+    let query = AssignedIssues::build_query(assigned_issues::Variables);
+    let issues = client
+        .req::<_, assigned_issues::ResponseData>(query)
+        .await?
+        .viewer
+        .assigned_issues
+        .nodes;
+    if issues.is_empty() {
+        bail!("no issue given and none assigned to you");
+    }
+
+    let labels = issues
+        .iter()
+        .map(|i| format!("{} {} ({})", i.identifier, i.title, i.state.name))
+        .collect::<Vec<_>>();
+    let i = prompt::pick("Pick an issue", &labels, "")?;
+    Ok(issues[i].identifier.clone())
+}
+
+/// `id` if it is given, otherwise the issue of the checked out branch. If there is none, e.g. because HEAD is detached
+/// or the branch isn't linked to an issue, the user can pick one of their issues. Errors talking to Linear are returned
+/// as they are.
+pub async fn or_current(client: &Client, id: Option<String>) -> Result<String> {
+    if let Some(id) = id {
+        return Ok(id);
+    }
+    if !prompt::interactive() {
+        return request().client(client).call().await;
+    }
+
+    let branch = match git::current_branch() {
+        Ok(branch) => branch,
+        Err(err) => {
+            debug!(?err, "could not get the checked out branch");
+            None
+        }
+    };
+    let found = match &branch {
+        Some(branch) => find(client, branch).await?,
+        None => None,
+    };
+    match found {
+        Some(identifier) => Ok(identifier),
+        None => {
+            debug!(?branch, "no issue for the checked out branch");
+            pick_assigned(client).await
+        }
+    }
+}
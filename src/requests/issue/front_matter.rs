@@ -0,0 +1,359 @@
+//! Issues as a Markdown document with a YAML front matter block, for composing them in `$EDITOR`:
+//!
+//! ```text
+//! ---
+//! title: Fix the login form
+//! state: In Progress
+//! assignee: robert
+//! labels: [bug, frontend]
+//! priority: high
+//! estimate: 3
+//! due_date: 2024-09-01
+//! ---
+//!
+//! The description.
+//! ```
+
+use super::{
+    changes::Changes,
+    show,
+};
+use crate::shared::Priority;
+use chrono::NaiveDate;
+use clap::ValueEnum as _;
+use eyre::{
+    bail,
+    Context as _,
+    Result,
+};
+use serde::{
+    Deserialize,
+    Deserializer,
+    Serialize,
+};
+
+const DELIMITER: &str = "---";
+
+/// Fields that are `None` (or empty) are left empty in the front matter.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FrontMatter {
+    #[serde(default, deserialize_with = "scalar")]
+    pub title: Option<String>,
+    /// Name or type of a workflow state.
+    #[serde(default, deserialize_with = "scalar")]
+    pub state: Option<String>,
+    /// Display name, name or email of a user, or `me`.
+    #[serde(default, deserialize_with = "scalar")]
+    pub assignee: Option<String>,
+    #[serde(default, deserialize_with = "labels")]
+    pub labels: Vec<String>,
+    /// `urgent`, `high`, `medium` or `low`.
+    #[serde(default, deserialize_with = "scalar")]
+    pub priority: Option<String>,
+    #[serde(default)]
+    pub estimate: Option<i64>,
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Document {
+    pub front_matter: FrontMatter,
+    pub description: String,
+}
+
+/// Accept any scalar for text fields, so that e.g. a title like `404` doesn't fail because it is read as a number.
+fn scalar<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    let value = Option::<serde_yaml::Value>::deserialize(deserializer)?;
+    match value {
+        None | Some(serde_yaml::Value::Null) => Ok(None),
+        Some(serde_yaml::Value::String(s)) => Ok(Some(s.trim().to_string()).filter(|s| !s.is_empty())),
+        Some(serde_yaml::Value::Number(n)) => Ok(Some(n.to_string())),
+        Some(serde_yaml::Value::Bool(b)) => Ok(Some(b.to_string())),
+        Some(_) => Err(serde::de::Error::custom("expected a single value")),
+    }
+}
+
+/// Accept a list of labels as well as a single label, which is an easy mistake to make in YAML.
+fn labels<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Labels {
+        Many(Vec<String>),
+        One(String),
+    }
+
+    Ok(match Option::<Labels>::deserialize(deserializer)? {
+        None => Vec::new(),
+        Some(Labels::Many(labels)) => labels,
+        Some(Labels::One(label)) => label
+            .split(',')
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect(),
+    })
+}
+
+fn priority_name(priority: Priority) -> Option<String> {
+    match priority {
+        Priority::None => None,
+        priority => priority.to_possible_value().map(|v| v.get_name().to_string()),
+    }
+}
+
+impl From<&show::Issue> for Document {
+    fn from(issue: &show::Issue) -> Self {
+        let priority = Priority::value_variants()
+            .iter()
+            .find(|p| **p as i64 == issue.priority as i64)
+            .copied()
+            .and_then(priority_name);
+
+        Self {
+            front_matter: FrontMatter {
+                // Trimmed like the parsed values, so that they only differ if they were edited.
+                title: Some(issue.title.trim().to_string()),
+                state: Some(issue.state.name.clone()),
+                assignee: issue.assignee.as_ref().map(|a| a.display_name.clone()),
+                labels: issue.labels.nodes.iter().map(|l| l.name.clone()).collect(),
+                priority,
+                estimate: issue.estimate.map(|e| e as i64),
+                due_date: issue.due_date,
+            },
+            description: issue.description.as_deref().unwrap_or_default().trim().to_string(),
+        }
+    }
+}
+
+/// The starting point for a new issue, filled in from the command line.
+impl From<&Changes> for Document {
+    fn from(changes: &Changes) -> Self {
+        Self {
+            front_matter: FrontMatter {
+                title: changes.title.clone(),
+                state: changes.state.clone(),
+                assignee: changes.assignee.clone(),
+                labels: changes.labels.clone().unwrap_or_default(),
+                priority: changes.priority.and_then(priority_name),
+                estimate: changes.estimate.flatten(),
+                due_date: changes.due_date.flatten(),
+            },
+            description: changes.description.as_deref().unwrap_or_default().trim().to_string(),
+        }
+    }
+}
+
+/// Render a value as YAML on a single line, quoting it if needed.
+fn yaml(value: impl Serialize) -> String {
+    serde_yaml::to_string(&value).unwrap().trim_end().to_string()
+}
+
+impl Document {
+    pub fn render(&self) -> String {
+        let FrontMatter {
+            title,
+            state,
+            assignee,
+            labels,
+            priority,
+            estimate,
+            due_date,
+        } = &self.front_matter;
+        let field = |name: &str, value: Option<String>| match value {
+            Some(value) => format!("{name}: {value}\n"),
+            None => format!("{name}:\n"),
+        };
+
+        let mut text = String::new();
+        text.push_str(DELIMITER);
+        text.push('\n');
+        text.push_str("# Leave a field empty to unset it. priority is one of urgent, high, medium or low.\n");
+        text.push_str("# Values containing ': ' have to be quoted.\n");
+        text.push_str(&field("title", title.as_ref().map(yaml)));
+        text.push_str(&field("state", state.as_ref().map(yaml)));
+        text.push_str(&field("assignee", assignee.as_ref().map(yaml)));
+        let labels = labels.iter().map(yaml).collect::<Vec<_>>().join(", ");
+        text.push_str(&field("labels", Some(format!("[{labels}]"))));
+        text.push_str(&field("priority", priority.as_ref().map(yaml)));
+        text.push_str(&field("estimate", estimate.map(|e| e.to_string())));
+        text.push_str(&field("due_date", due_date.map(|d| d.to_string())));
+        text.push_str(DELIMITER);
+        text.push_str("\n\n");
+        text.push_str(self.description.trim());
+        text.push('\n');
+        text
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.trim_start_matches('\u{feff}').lines();
+        if lines.next().map(str::trim_end) != Some(DELIMITER) {
+            bail!("expected the issue to start with a '{DELIMITER}' line");
+        }
+
+        let mut yaml = String::new();
+        let mut closed = false;
+        for line in lines.by_ref() {
+            if line.trim_end() == DELIMITER {
+                closed = true;
+                break;
+            }
+            yaml.push_str(line);
+            yaml.push('\n');
+        }
+        if !closed {
+            bail!("expected a '{DELIMITER}' line after the front matter");
+        }
+
+        let front_matter = serde_yaml::from_str(&yaml).context("could not parse the front matter")?;
+        let description = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+        Ok(Self {
+            front_matter,
+            description,
+        })
+    }
+
+    /// The changes that turn `self` into `edited`, fields that were not touched are left out. Diffing against
+    /// `Document::default()` gives all fields that are set, e.g. for a new issue.
+    pub fn changes(&self, edited: &Document) -> Result<Changes> {
+        fn changed<T: PartialEq + Clone>(old: &T, new: &T) -> Option<T> {
+            (old != new).then(|| new.clone())
+        }
+
+        let (old, new) = (&self.front_matter, &edited.front_matter);
+        if new.title.is_none() {
+            bail!("the title is empty");
+        }
+        if new.state.is_none() && old.state.is_some() {
+            bail!("the state can't be empty");
+        }
+        let priority = match changed(&old.priority, &new.priority) {
+            Some(Some(priority)) => Some(Priority::from_str(&priority, true).map_err(|_| {
+                eyre::eyre!("unknown priority {priority:?}, expected one of urgent, high, medium, low or nothing")
+            })?),
+            Some(None) => Some(Priority::None),
+            None => None,
+        };
+
+        Ok(Changes {
+            title: changed(&old.title, &new.title).flatten(),
+            description: changed(&self.description, &edited.description),
+            state: changed(&old.state, &new.state).flatten(),
+            assignee: changed(&old.assignee, &new.assignee).map(|a| a.unwrap_or_else(|| "none".to_string())),
+            labels: changed(&old.labels, &new.labels),
+            priority,
+            cycle: None,
+            project: None,
+            estimate: changed(&old.estimate, &new.estimate),
+            due_date: changed(&old.due_date, &new.due_date),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> Document {
+        Document {
+            front_matter: FrontMatter {
+                title: Some("Fix: the login form".to_string()),
+                state: Some("In Progress".to_string()),
+                assignee: Some("robert".to_string()),
+                labels: vec!["bug".to_string(), "needs: design".to_string()],
+                priority: Some("high".to_string()),
+                estimate: Some(3),
+                due_date: NaiveDate::from_ymd_opt(2024, 9, 1),
+            },
+            description: "The description.\n\n---\n\nAfter a rule.".to_string(),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let document = document();
+        assert_eq!(Document::parse(&document.render()).unwrap(), document);
+        assert_eq!(
+            Document::parse(&Document::default().render()).unwrap(),
+            Document::default()
+        );
+    }
+
+    #[test]
+    fn quotes_values() {
+        let text = document().render();
+        assert!(text.contains("title: 'Fix: the login form'\n"), "{text}");
+        assert!(text.contains("labels: [bug, 'needs: design']\n"), "{text}");
+        assert!(text.contains("due_date: 2024-09-01\n"), "{text}");
+    }
+
+    #[test]
+    fn parses_loose_values() {
+        let text = "\u{feff}---\ntitle: 404\nlabels: bug, frontend\nstate:\n---\ndescription\n";
+        let document = Document::parse(text).unwrap();
+        assert_eq!(document.front_matter.title.as_deref(), Some("404"));
+        assert_eq!(document.front_matter.labels, ["bug", "frontend"]);
+        assert_eq!(document.front_matter.state, None);
+        assert_eq!(document.description, "description");
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(Document::parse("title: x\n").is_err());
+        assert!(Document::parse("---\ntitle: x\n").is_err());
+        assert!(Document::parse("---\ntitle: [x, y]\n---\n").is_err());
+        assert!(Document::parse("---\ntitel: x\n---\n").is_err());
+        assert!(Document::parse("---\nestimate: many\n---\n").is_err());
+    }
+
+    #[test]
+    fn changes_only_edited_fields() {
+        let original = document();
+        assert!(original.changes(&original).unwrap().is_empty());
+
+        let mut edited = original.clone();
+        edited.front_matter.title = Some("Fix the login form".to_string());
+        edited.front_matter.assignee = None;
+        edited.front_matter.priority = None;
+        edited.front_matter.estimate = None;
+        let changes = original.changes(&edited).unwrap();
+        assert_eq!(changes.title.as_deref(), Some("Fix the login form"));
+        assert_eq!(changes.assignee.as_deref(), Some("none"));
+        assert!(matches!(changes.priority, Some(Priority::None)));
+        assert_eq!(changes.estimate, Some(None));
+        assert_eq!(changes.state, None);
+        assert_eq!(changes.labels, None);
+        assert_eq!(changes.description, None);
+        assert_eq!(changes.due_date, None);
+    }
+
+    #[test]
+    fn changes_for_new_issue() {
+        let changes = Document::default().changes(&document()).unwrap();
+        assert_eq!(changes.title.as_deref(), Some("Fix: the login form"));
+        assert_eq!(changes.state.as_deref(), Some("In Progress"));
+        assert_eq!(
+            changes.labels,
+            Some(vec!["bug".to_string(), "needs: design".to_string()])
+        );
+        assert!(matches!(changes.priority, Some(Priority::High)));
+        assert_eq!(changes.estimate, Some(Some(3)));
+        assert_eq!(changes.description.as_deref(), Some(document().description.as_str()));
+    }
+
+    #[test]
+    fn changes_errors() {
+        let original = document();
+        let mut edited = original.clone();
+        edited.front_matter.title = None;
+        assert!(original.changes(&edited).is_err());
+
+        let mut edited = original.clone();
+        edited.front_matter.state = None;
+        assert!(original.changes(&edited).is_err());
+
+        let mut edited = original.clone();
+        edited.front_matter.priority = Some("very".to_string());
+        assert!(original.changes(&edited).is_err());
+    }
+}
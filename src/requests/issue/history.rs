@@ -0,0 +1,204 @@
+use super::changes::{
+    cycle_label,
+    priority_label,
+};
+use crate::{
+    client::Client,
+    dates,
+    style,
+};
+use eyre::Result;
+use graphql_client::GraphQLQuery;
+use serde::Serialize;
+
+type DateTime = chrono::DateTime<chrono::Utc>;
+type TimelessDate = chrono::NaiveDate;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-history.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug, Clone, Serialize, Deserialize"
+)]
+struct IssueHistory;
+
+pub type Event = issue_history::IssueHistoryIssueHistoryNodes;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct History {
+    pub identifier: String,
+    pub title: String,
+    /// Oldest first.
+    pub events: Vec<Event>,
+}
+
+#[builder]
+pub async fn request(client: &Client, issue_id: String) -> Result<History> {
+    let mut after = None;
+    let mut events = Vec::new();
+
+    loop {
+        let query = IssueHistory::build_query(issue_history::Variables {
+            issue_id: issue_id.clone(),
+            after,
+        });
+        let issue = client.req::<_, issue_history::ResponseData>(query).await?.issue;
+        events.extend(issue.history.nodes);
+
+        if !issue.history.page_info.has_next_page {
+            events.sort_by_key(|e| e.created_at);
+            return Ok(History {
+                identifier: issue.identifier,
+                title: issue.title,
+                events,
+            });
+        }
+        after = issue.history.page_info.end_cursor;
+    }
+}
+
+/// Who made the change. Changes without actor were made by Linear itself, e.g. by auto-closing.
+fn actor(event: &Event) -> String {
+    let actors = event.actors.iter().flatten().map(|a| a.display_name.as_str()).collect::<Vec<_>>();
+    match (actors.as_slice(), &event.bot_actor) {
+        ([], Some(bot)) => bot.name.clone().unwrap_or_else(|| "bot".to_string()),
+        ([], None) => "Linear".to_string(),
+        (actors, _) => actors.join(", "),
+    }
+}
+
+fn or_none(value: Option<impl std::fmt::Display>) -> String {
+    value.map_or_else(|| "none".to_string(), |v| v.to_string())
+}
+
+/// The changes of a single history entry, one line per field.
+fn changes(event: &Event) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    if let (Some(from), Some(to)) = (&event.from_title, &event.to_title) {
+        lines.push(format!("title: {from} → {to}"));
+    }
+    if event.updated_description == Some(true) {
+        lines.push("description updated".to_string());
+    }
+    if let (Some(from), Some(to)) = (&event.from_state, &event.to_state) {
+        lines.push(format!(
+            "state: {} → {}",
+            style::fg(&from.name, &from.color),
+            style::fg(&to.name, &to.color)
+        ));
+    }
+    if event.from_assignee.is_some() || event.to_assignee.is_some() {
+        lines.push(format!(
+            "assignee: {} → {}",
+            or_none(event.from_assignee.as_ref().map(|a| &a.display_name)),
+            or_none(event.to_assignee.as_ref().map(|a| &a.display_name))
+        ));
+    }
+    if let (Some(from), Some(to)) = (event.from_priority, event.to_priority) {
+        lines.push(format!(
+            "priority: {} → {}",
+            style::priority(from, priority_label(from as i64)),
+            style::priority(to, priority_label(to as i64))
+        ));
+    }
+    if event.from_estimate.is_some() || event.to_estimate.is_some() {
+        lines.push(format!(
+            "estimate: {} → {}",
+            or_none(event.from_estimate),
+            or_none(event.to_estimate)
+        ));
+    }
+    let added = event.added_labels.iter().flatten().map(|l| style::fg(format!("+{}", l.name), &l.color));
+    let removed = event.removed_labels.iter().flatten().map(|l| style::fg(format!("-{}", l.name), &l.color));
+    let labels = added.chain(removed).collect::<Vec<_>>();
+    if !labels.is_empty() {
+        lines.push(format!("labels: {}", labels.join(" ")));
+    }
+    if event.from_cycle.is_some() || event.to_cycle.is_some() {
+        lines.push(format!(
+            "cycle: {} → {}",
+            or_none(event.from_cycle.as_ref().map(|c| cycle_label(c.number, c.name.as_deref()))),
+            or_none(event.to_cycle.as_ref().map(|c| cycle_label(c.number, c.name.as_deref())))
+        ));
+    }
+    if event.from_project.is_some() || event.to_project.is_some() {
+        lines.push(format!(
+            "project: {} → {}",
+            or_none(event.from_project.as_ref().map(|p| &p.name)),
+            or_none(event.to_project.as_ref().map(|p| &p.name))
+        ));
+    }
+    if event.from_parent.is_some() || event.to_parent.is_some() {
+        lines.push(format!(
+            "parent: {} → {}",
+            or_none(event.from_parent.as_ref().map(|p| &p.identifier)),
+            or_none(event.to_parent.as_ref().map(|p| &p.identifier))
+        ));
+    }
+    if let (Some(from), Some(to)) = (&event.from_team, &event.to_team) {
+        lines.push(format!("team: {} → {}", from.key, to.key));
+    }
+    if event.from_due_date.is_some() || event.to_due_date.is_some() {
+        lines.push(format!(
+            "due date: {} → {}",
+            or_none(event.from_due_date.map(dates::fmt_day)),
+            or_none(event.to_due_date.map(dates::fmt_day))
+        ));
+    }
+    for relation in event.relation_changes.iter().flatten() {
+        lines.push(format!("relation {}: {}", relation.type_, relation.identifier));
+    }
+    for (flag, text) in [
+        (event.archived, "archived"),
+        (event.trashed, "moved to trash"),
+        (event.auto_closed, "closed automatically"),
+        (event.auto_archived, "archived automatically"),
+    ] {
+        if flag == Some(true) {
+            lines.push(text.to_string());
+        }
+    }
+
+    lines
+}
+
+pub fn print(res: Result<History>, json: bool, full_width: bool) {
+    use comfy_table::*;
+
+    let res = match res {
+        Ok(res) => res,
+        Err(err) => {
+            eprintln!("{:?}", err);
+            return;
+        }
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&res).unwrap());
+        return;
+    }
+
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::NOTHING);
+    if full_width {
+        table.set_content_arrangement(comfy_table::ContentArrangement::Disabled);
+    } else {
+        table.set_content_arrangement(comfy_table::ContentArrangement::DynamicFullWidth);
+    }
+
+    for event in &res.events {
+        let changes = changes(event);
+        if changes.is_empty() {
+            continue;
+        }
+        table.add_row([
+            Cell::new(dates::fmt(event.created_at)),
+            Cell::new(actor(event)),
+            Cell::new(changes.join("\n")),
+        ]);
+    }
+
+    println!("{} {}", style::bold(&res.identifier), res.title);
+    println!("{table}");
+}
@@ -1,9 +1,14 @@
+use super::changes::cycle_label;
 use crate::{
     client::Client,
-    shared::{IssueState, SortBy},
+    dates,
+    shared::{GroupBy, IssueState, ListFormat, SortBy},
+    style,
 };
 use eyre::Result;
 use graphql_client::GraphQLQuery;
+use serde::Serialize;
+use std::collections::BTreeMap;
 
 #[derive(GraphQLQuery)]
 #[graphql(
@@ -23,6 +28,9 @@ pub async fn request(
     sort_by: SortBy,
     assignee: Option<String>,
     state: Option<Vec<IssueState>>,
+    parent: Option<String>,
+    #[builder(default)] blocked: bool,
+    #[builder(default)] include_archived: bool,
 ) -> Result<Vec<Issue>> {
     const PER_PAGE: usize = 100;
     let per_page = n.map(|n| n.min(PER_PAGE)).unwrap_or(PER_PAGE);
@@ -43,7 +51,7 @@ pub async fn request(
             order_by: Some(order_by.clone()),
             after,
             before: None,
-            include_archived: None,
+            include_archived: include_archived.then_some(true),
             last: None,
         });
 
@@ -64,6 +72,22 @@ pub async fn request(
                         true
                     }
                 })
+                .filter(|i| {
+                    if let Some(parent) = &parent {
+                        i.parent
+                            .as_ref()
+                            .is_some_and(|p| p.identifier.eq_ignore_ascii_case(parent) || &p.id == parent)
+                    } else {
+                        true
+                    }
+                })
+                .filter(|i| {
+                    // An issue is blocked as long as any issue blocking it is not done yet.
+                    !blocked
+                        || i.inverse_relations.nodes.iter().any(|r| {
+                            r.type_ == "blocks" && !matches!(r.issue.state.type_.as_str(), "completed" | "canceled")
+                        })
+                })
                 .filter(|i| {
                     let state_t = i.state.type_.as_str();
                     if let Some(state) = &state {
@@ -97,14 +121,124 @@ pub async fn request(
     }
 }
 
-fn fmt_date(date: DateTime) -> String {
-    let date = date.with_timezone(&chrono::Local);
-    date.format("%Y-%m-%d %H:%M").to_string()
+/// A group of issues for `--group-by`. Groups are sorted by `order` first, e.g. the state type or the priority, then by
+/// name. Users, projects and cycles are told apart by `id`, so that e.g. cycle 3 of two teams doesn't end up in one
+/// group.
+#[derive(Debug, Clone)]
+struct GroupKey {
+    order: u32,
+    name: String,
+    id: Option<String>,
+    /// Only used to render the name, two states or labels with the same name but another color are one group.
+    color: Option<String>,
 }
 
-pub fn print(res: Result<Vec<Issue>>, json: bool, full_width: bool) {
-    use comfy_table::*;
+impl GroupKey {
+    fn new(order: u32, name: impl Into<String>) -> Self {
+        Self {
+            order,
+            name: name.into(),
+            id: None,
+            color: None,
+        }
+    }
+
+    fn sort_key(&self) -> (u32, &str, Option<&str>) {
+        (self.order, &self.name, self.id.as_deref())
+    }
+}
+
+impl PartialEq for GroupKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.sort_key() == other.sort_key()
+    }
+}
+
+impl Eq for GroupKey {}
+
+impl PartialOrd for GroupKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GroupKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+#[derive(Serialize)]
+struct Group<'a> {
+    group: &'a str,
+    count: usize,
+    issues: &'a [Issue],
+}
+
+/// The groups an issue belongs to. With `--group-by label` an issue is in the group of each of its labels, otherwise
+/// in exactly one group. Issues without a value for the field come last.
+fn group_keys(issue: &Issue, group_by: GroupBy) -> Vec<GroupKey> {
+    const LAST: u32 = u32::MAX;
+
+    match group_by {
+        GroupBy::State => {
+            let order = ["backlog", "unstarted", "started", "completed", "canceled"]
+                .iter()
+                .position(|t| *t == issue.state.type_)
+                .map_or(LAST, |i| i as u32);
+            vec![GroupKey {
+                color: Some(issue.state.color.clone()),
+                ..GroupKey::new(order, &issue.state.name)
+            }]
+        }
+        GroupBy::Assignee => vec![match &issue.assignee {
+            Some(assignee) => GroupKey {
+                id: Some(assignee.id.clone()),
+                ..GroupKey::new(0, &assignee.display_name)
+            },
+            None => GroupKey::new(LAST, "unassigned"),
+        }],
+        GroupBy::Project => vec![match &issue.project {
+            Some(project) => GroupKey {
+                id: Some(project.id.clone()),
+                ..GroupKey::new(0, &project.name)
+            },
+            None => GroupKey::new(LAST, "no project"),
+        }],
+        GroupBy::Label if issue.labels.nodes.is_empty() => vec![GroupKey::new(LAST, "no label")],
+        GroupBy::Label => issue
+            .labels
+            .nodes
+            .iter()
+            .map(|l| GroupKey {
+                color: Some(l.color.clone()),
+                ..GroupKey::new(0, &l.name)
+            })
+            .collect(),
+        GroupBy::Priority if issue.priority == 0.0 => vec![GroupKey::new(LAST, &issue.priority_label)],
+        GroupBy::Priority => vec![GroupKey::new(issue.priority as u32, &issue.priority_label)],
+        GroupBy::Cycle => vec![match &issue.cycle {
+            Some(cycle) => GroupKey {
+                id: Some(cycle.id.clone()),
+                ..GroupKey::new(cycle.number as u32, cycle_label(cycle.number, cycle.name.as_deref()))
+            },
+            None => GroupKey::new(LAST, "no cycle"),
+        }],
+        GroupBy::Team => vec![GroupKey::new(0, &issue.team.key)],
+    }
+}
+
+fn group(issues: Vec<Issue>, group_by: GroupBy) -> Vec<(GroupKey, Vec<Issue>)> {
+    let mut groups = BTreeMap::<GroupKey, Vec<Issue>>::new();
+    for issue in issues {
+        for key in group_keys(&issue, group_by) {
+            groups.entry(key).or_default().push(issue.clone());
+        }
+    }
+    groups.into_iter().collect()
+}
 
+pub fn print(res: Result<Vec<Issue>>, format: ListFormat, group_by: Option<GroupBy>, full_width: bool, show_url: bool) {
     let res = match res {
         Ok(res) => res,
         Err(err) => {
@@ -113,11 +247,62 @@ pub fn print(res: Result<Vec<Issue>>, json: bool, full_width: bool) {
         }
     };
 
-    if json {
-        println!("{}", serde_json::to_string_pretty(&res).unwrap());
-        return;
+    let groups = group_by.map(|group_by| group(res.clone(), group_by));
+
+    match format {
+        ListFormat::Table => {}
+        ListFormat::Json => {
+            match &groups {
+                Some(groups) => {
+                    let groups = groups
+                        .iter()
+                        .map(|(key, issues)| Group {
+                            group: &key.name,
+                            count: issues.len(),
+                            issues,
+                        })
+                        .collect::<Vec<_>>();
+                    println!("{}", serde_json::to_string_pretty(&groups).unwrap());
+                }
+                None => println!("{}", serde_json::to_string_pretty(&res).unwrap()),
+            }
+            return;
+        }
+        ListFormat::Ndjson => {
+            for issue in &res {
+                println!("{}", serde_json::to_string(issue).unwrap());
+            }
+            return;
+        }
+        ListFormat::Ids => {
+            for issue in &res {
+                println!("{}", issue.identifier);
+            }
+            return;
+        }
     }
 
+    match groups {
+        Some(groups) => {
+            for (i, (key, issues)) in groups.into_iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                let name = match &key.color {
+                    Some(color) => style::fg(&key.name, color),
+                    None => key.name,
+                };
+                println!("{} ({})", style::bold(name), issues.len());
+                println!("{}", table(issues, full_width, show_url));
+            }
+        }
+        None => println!("{}", table(res, full_width, show_url)),
+    }
+}
+
+fn table(issues: Vec<Issue>, full_width: bool, show_url: bool) -> comfy_table::Table {
+    use comfy_table::*;
+
     let mut table = Table::new();
     table.load_preset(comfy_table::presets::NOTHING);
     if full_width {
@@ -126,7 +311,7 @@ pub fn print(res: Result<Vec<Issue>>, json: bool, full_width: bool) {
         table.set_content_arrangement(comfy_table::ContentArrangement::DynamicFullWidth);
     }
 
-    table.add_row([
+    let mut header = vec![
         Cell::new("id"),
         Cell::new("title"),
         Cell::new("state"),
@@ -135,52 +320,86 @@ pub fn print(res: Result<Vec<Issue>>, json: bool, full_width: bool) {
         Cell::new("updated_at"),
         // Cell::new("parent"),
         Cell::new("priority"),
-        // Cell::new("project"),
-        // Cell::new("labels"),
-        Cell::new("url"),
-    ]);
+        Cell::new("project"),
+        Cell::new("labels"),
+    ];
+    if show_url {
+        header.push(Cell::new("url"));
+    }
+    table.add_row(header);
 
     table
         .column_iter_mut()
-        .last()
+        .next()
         .unwrap()
         .set_constraint(ColumnConstraint::ContentWidth);
+    if show_url {
+        table
+            .column_iter_mut()
+            .last()
+            .unwrap()
+            .set_constraint(ColumnConstraint::ContentWidth);
+    }
 
-    for issue in res {
+    for issue in issues {
         let Issue {
             url,
             identifier,
             title,
             created_at,
             updated_at,
+            archived_at,
             parent: _,
             priority,
             priority_label,
-            project: _,
+            project,
             assignee,
             state,
-            labels: _,
+            labels,
             creator: _,
             ..
         } = issue;
 
-        let state = format!("{} ({})", state.name, state.type_);
-        let priority = if priority == 0.0 { String::new() } else { priority_label };
+        let state = style::fg(format!("{} ({})", state.name, state.type_), &state.color);
+        let state = if archived_at.is_some() {
+            format!("{state} (archived)")
+        } else {
+            state
+        };
+        let priority = if priority == 0.0 {
+            String::new()
+        } else {
+            style::priority(priority, priority_label)
+        };
+        let labels = labels
+            .nodes
+            .into_iter()
+            .map(|l| style::fg(l.name, &l.color))
+            .collect::<Vec<_>>()
+            .join(", ");
 
-        table.add_row([
-            Cell::new(identifier),
+        let assignee = assignee
+            .map(|a| style::link(a.display_name, &a.url))
+            .unwrap_or_default();
+        let project = project.map(|p| style::link(p.name, &p.url)).unwrap_or_default();
+
+        let mut row = vec![
+            Cell::new(style::link(identifier, &url)),
             Cell::new(title),
             Cell::new(state),
-            Cell::new(assignee.map(|a| a.display_name).unwrap_or_default()),
-            Cell::new(fmt_date(created_at)),
-            Cell::new(fmt_date(updated_at)),
+            Cell::new(assignee),
+            Cell::new(dates::fmt(created_at)),
+            Cell::new(dates::fmt(updated_at)),
             // Cell::new(parent.map(|p| p.id).unwrap_or_default()),
             Cell::new(priority),
-            // Cell::new(project.map(|p| p.id).unwrap_or_default()),
-            // Cell::new(labels.nodes.into_iter().map(|n| n.id).collect::<Vec<_>>().join(", ")),
-            Cell::new(url),
-        ]);
+            Cell::new(project),
+            Cell::new(labels),
+        ];
+        if show_url {
+            row.push(Cell::new(url));
+        }
+        table.add_row(row);
     }
 
-    println!("{table}")
+    table
 }
@@ -1,3 +1,17 @@
+pub mod archive;
+pub mod attachment;
+pub mod bulk_update;
+pub mod changes;
+pub mod comment;
+pub mod create;
+pub mod current;
+pub mod front_matter;
+pub mod history;
 pub mod list;
-pub mod update;
+pub mod relation;
 pub mod show;
+pub mod subscription;
+pub mod template;
+pub mod tree;
+pub mod update;
+pub mod upload;
@@ -0,0 +1,212 @@
+use crate::client::{
+    Client,
+    DryRun,
+};
+use eyre::{
+    bail,
+    Result,
+};
+use graphql_client::GraphQLQuery;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-relation.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug, Clone, Serialize, Deserialize"
+)]
+struct IssueRelations;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-relation.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct IssueRelationCreate;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-relation.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct IssueRelationDelete;
+
+pub type Issue = issue_relations::IssueRelationsIssue;
+
+/// A relation as seen from the issue given on the command line.
+#[derive(Clone, Copy, Debug)]
+pub enum Relation {
+    Blocks,
+    BlockedBy,
+    DuplicateOf,
+    Related,
+}
+
+impl std::fmt::Display for Relation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Relation::Blocks => write!(f, "blocks"),
+            Relation::BlockedBy => write!(f, "is blocked by"),
+            Relation::DuplicateOf => write!(f, "is a duplicate of"),
+            Relation::Related => write!(f, "is related to"),
+        }
+    }
+}
+
+#[builder]
+pub async fn request(client: &Client, issue_id: String) -> Result<Issue> {
+    let query = IssueRelations::build_query(issue_relations::Variables { issue_id });
+    client
+        .req::<_, issue_relations::ResponseData>(query)
+        .await
+        .map(|res| res.issue)
+}
+
+/// A relation as Linear stores it, with enough information to delete or recreate it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stored {
+    pub id: String,
+    /// `blocks`, `duplicate` or `related`.
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub issue_id: String,
+    pub related_issue_id: String,
+}
+
+/// Create a relation between two issues. Linear stores "blocked by" as a "blocks" relation with swapped issues. Returns
+/// the created relation and the identifiers of both issues.
+#[builder]
+pub async fn create(
+    client: &Client,
+    issue_id: String,
+    relation: Relation,
+    other_id: String,
+) -> Result<(Stored, String, String)> {
+    let issue = request().client(client).issue_id(issue_id).call().await?;
+    let other = request().client(client).issue_id(other_id).call().await?;
+
+    let (issue_id, related_issue_id, type_) = match relation {
+        Relation::Blocks => (issue.id, other.id, "blocks"),
+        Relation::BlockedBy => (other.id, issue.id, "blocks"),
+        Relation::DuplicateOf => (issue.id, other.id, "duplicate"),
+        Relation::Related => (issue.id, other.id, "related"),
+    };
+
+    let id = create_stored(client, issue_id.clone(), related_issue_id.clone(), type_).await?;
+    let stored = Stored {
+        id,
+        type_: type_.to_string(),
+        issue_id,
+        related_issue_id,
+    };
+    Ok((stored, issue.identifier, other.identifier))
+}
+
+/// Create a relation of a given type between two issues, given by their ids. Returns the id of the relation.
+async fn create_stored(client: &Client, issue_id: String, related_issue_id: String, type_: &str) -> Result<String> {
+    use issue_relation_create::IssueRelationType;
+
+    let type_ = match type_ {
+        "blocks" => IssueRelationType::blocks,
+        "duplicate" => IssueRelationType::duplicate,
+        "related" => IssueRelationType::related,
+        other => IssueRelationType::Other(other.to_string()),
+    };
+    let query = IssueRelationCreate::build_query(issue_relation_create::Variables {
+        input: issue_relation_create::IssueRelationCreateInput {
+            id: None,
+            type_,
+            issue_id,
+            related_issue_id,
+        },
+    });
+    let response = client
+        .req::<_, issue_relation_create::ResponseData>(query)
+        .await?
+        .issue_relation_create;
+    if !response.success {
+        bail!("could not create relation");
+    }
+
+    Ok(response.issue_relation.id)
+}
+
+/// Recreate a relation that was deleted. It gets a new id, which is returned.
+pub async fn recreate(client: &Client, relation: &Stored) -> Result<String> {
+    create_stored(
+        client,
+        relation.issue_id.clone(),
+        relation.related_issue_id.clone(),
+        &relation.type_,
+    )
+    .await
+}
+
+/// Remove all relations between two issues, regardless of their type and direction. Returns the relations that were
+/// removed.
+#[builder]
+pub async fn delete(client: &Client, issue_id: String, other_id: String) -> Result<Vec<Stored>> {
+    let issue = request().client(client).issue_id(issue_id).call().await?;
+    let other = request().client(client).issue_id(other_id).call().await?;
+
+    let relations = issue
+        .relations
+        .nodes
+        .iter()
+        .filter(|r| r.related_issue.id == other.id)
+        .map(|r| Stored {
+            id: r.id.clone(),
+            type_: r.type_.clone(),
+            issue_id: issue.id.clone(),
+            related_issue_id: other.id.clone(),
+        })
+        .chain(
+            issue
+                .inverse_relations
+                .nodes
+                .iter()
+                .filter(|r| r.issue.id == other.id)
+                .map(|r| Stored {
+                    id: r.id.clone(),
+                    type_: r.type_.clone(),
+                    issue_id: other.id.clone(),
+                    related_issue_id: issue.id.clone(),
+                }),
+        )
+        .collect::<Vec<_>>();
+
+    if relations.is_empty() {
+        bail!("{} and {} are not related", issue.identifier, other.identifier);
+    }
+
+    for relation in &relations {
+        match delete_stored(client, &relation.id).await {
+            // Show the mutations for all relations, not just the first one.
+            Err(err) if err.is::<DryRun>() => continue,
+            res => res?,
+        }
+    }
+    if client.is_dry_run() {
+        return Err(DryRun.into());
+    }
+
+    Ok(relations)
+}
+
+/// Delete a single relation by its id.
+pub async fn delete_stored(client: &Client, id: &str) -> Result<()> {
+    let query = IssueRelationDelete::build_query(issue_relation_delete::Variables { id: id.to_string() });
+    let response = client
+        .req::<_, issue_relation_delete::ResponseData>(query)
+        .await?
+        .issue_relation_delete;
+    if !response.success {
+        bail!("could not delete relation {id}");
+    }
+    Ok(())
+}
@@ -1,4 +1,9 @@
-use crate::client::Client;
+use super::tree;
+use crate::{
+    client::Client,
+    dates,
+    style,
+};
 use eyre::Result;
 use graphql_client::GraphQLQuery;
 
@@ -24,12 +29,16 @@ pub async fn request(client: &Client, issue_id: String) -> Result<Issue> {
         .map(|res| res.issue)
 }
 
-fn fmt_date(date: DateTime) -> String {
-    let date = date.with_timezone(&chrono::Local);
-    date.format("%Y-%m-%d %H:%M").to_string()
+/// The issue as JSON, with its sub-issues as `children` if they were requested.
+pub fn to_json(issue: &Issue, children: Option<tree::Node>) -> serde_json::Value {
+    let mut value = serde_json::to_value(issue).unwrap();
+    if let Some(children) = children {
+        value["children"] = serde_json::to_value(children.children).unwrap();
+    }
+    value
 }
 
-pub fn print(res: Result<Issue>, json: bool, full_width: bool) {
+pub fn print(res: Result<Issue>, children: Option<tree::Node>, json: bool, full_width: bool) {
     use comfy_table::*;
 
     let res = match res {
@@ -41,7 +50,7 @@ pub fn print(res: Result<Issue>, json: bool, full_width: bool) {
     };
 
     if json {
-        println!("{}", serde_json::to_string_pretty(&res).unwrap());
+        println!("{}", serde_json::to_string_pretty(&to_json(&res, children)).unwrap());
         return;
     }
 
@@ -52,14 +61,17 @@ pub fn print(res: Result<Issue>, json: bool, full_width: bool) {
     let Issue {
         url,
         updated_at,
+        archived_at,
         title,
         team,
         assignee,
         creator,
-        branch_name: _,
+        attachments,
+        branch_name,
         canceled_at,
         completed_at,
         created_at,
+        cycle,
         description,
         due_date,
         estimate,
@@ -70,30 +82,53 @@ pub fn print(res: Result<Issue>, json: bool, full_width: bool) {
         priority,
         priority_label,
         project,
+        relations,
+        inverse_relations,
         started_at,
         state,
         trashed,
     } = res;
 
-    let creator = creator.map(|u| u.display_name).unwrap_or_default();
-    let assignee = assignee.map(|u| u.display_name).unwrap_or_default();
+    let creator = creator.map(|u| style::link(u.display_name, &u.url)).unwrap_or_default();
+    let assignee = assignee.map(|u| style::link(u.display_name, &u.url)).unwrap_or_default();
     let team = team.key;
-    let labels = labels.nodes.into_iter().map(|l| l.name).collect::<Vec<_>>().join(", ");
-    let priority = format!("{} ({})", priority_label, priority);
+    let labels = labels
+        .nodes
+        .into_iter()
+        .map(|l| style::fg(l.name, &l.color))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let priority = style::priority(priority, format!("{} ({})", priority_label, priority));
     let state = if trashed.unwrap_or(false) {
-        format!("{} ({}) -- TRASHED", state.name, state.type_)
+        format!(
+            "{} -- TRASHED",
+            style::fg(format!("{} ({})", state.name, state.type_), &state.color)
+        )
+    } else if archived_at.is_some() {
+        format!(
+            "{} -- ARCHIVED",
+            style::fg(format!("{} ({})", state.name, state.type_), &state.color)
+        )
     } else {
-        format!("{} ({})", state.name, state.type_)
+        style::fg(format!("{} ({})", state.name, state.type_), &state.color)
     };
 
-    let created_at = fmt_date(created_at);
-    let updated_at = fmt_date(updated_at);
-    let started_at = started_at.map(fmt_date);
-    let completed_at = completed_at.map(fmt_date);
-    let canceled_at = canceled_at.map(fmt_date);
-    let due_date = due_date.map(|d| d.format("%Y-%m-%d").to_string());
+    let created_at = dates::fmt(created_at);
+    let updated_at = dates::fmt(updated_at);
+    let started_at = started_at.map(dates::fmt);
+    let completed_at = completed_at.map(dates::fmt);
+    let canceled_at = canceled_at.map(dates::fmt);
+    let due_date = due_date.map(|d| {
+        let formatted = dates::fmt_day(d);
+        let done = completed_at.is_some() || canceled_at.is_some();
+        if !done && d < dates::today() {
+            style::bold(formatted)
+        } else {
+            formatted
+        }
+    });
 
-    table.add_row([Cell::new("id"), Cell::new(&identifier)]);
+    table.add_row([Cell::new("id"), Cell::new(style::link(&identifier, &url))]);
     table.add_row([Cell::new("url"), Cell::new(&url)]);
 
     table.add_row([Cell::new("title"), Cell::new(&title)]);
@@ -105,12 +140,55 @@ pub fn print(res: Result<Issue>, json: bool, full_width: bool) {
         table.add_row([Cell::new("labels"), Cell::new(&labels)]);
     }
     table.add_row([Cell::new("priority"), Cell::new(&priority)]);
-    if let Some(parent) = parent.and_then(|p| p.parent) {
-        table.add_row([Cell::new("parent"), Cell::new(&parent.identifier)]);
+    if let Some(parent) = parent {
+        table.add_row([
+            Cell::new("parent"),
+            Cell::new(format!("{} {}", style::link(&parent.identifier, &parent.url), parent.title)),
+        ]);
+    }
+
+    // Relations are stored once, on one of the two issues. Depending on which side this issue is on, the same relation
+    // type reads differently.
+    let mut related = std::collections::BTreeMap::<&str, Vec<String>>::new();
+    for relation in &relations.nodes {
+        let name = match relation.type_.as_str() {
+            "blocks" => "blocks",
+            "duplicate" => "duplicate of",
+            _ => "related",
+        };
+        let issue = &relation.related_issue;
+        related
+            .entry(name)
+            .or_default()
+            .push(style::link(&issue.identifier, &issue.url));
+    }
+    for relation in &inverse_relations.nodes {
+        let name = match relation.type_.as_str() {
+            "blocks" => "blocked by",
+            "duplicate" => "duplicated by",
+            _ => "related",
+        };
+        let issue = &relation.issue;
+        related
+            .entry(name)
+            .or_default()
+            .push(style::link(&issue.identifier, &issue.url));
+    }
+    for (name, issues) in related {
+        table.add_row([Cell::new(name), Cell::new(issues.join(", "))]);
+    }
+
+    if let Some(cycle) = cycle {
+        let cycle = match cycle.name {
+            Some(name) => format!("{} ({name})", cycle.number),
+            None => cycle.number.to_string(),
+        };
+        table.add_row([Cell::new("cycle"), Cell::new(cycle)]);
     }
     if let Some(project) = project {
-        table.add_row([Cell::new("project"), Cell::new(&project.name)]);
+        table.add_row([Cell::new("project"), Cell::new(style::link(&project.name, &project.url))]);
     }
+    table.add_row([Cell::new("branch"), Cell::new(&branch_name)]);
 
     table.add_row([Cell::new("created at"), Cell::new(&created_at)]);
     table.add_row([Cell::new("updated at"), Cell::new(&updated_at)]);
@@ -130,9 +208,27 @@ pub fn print(res: Result<Issue>, json: bool, full_width: bool) {
         table.add_row([Cell::new("estimate"), Cell::new(estimate)]);
     }
 
+    if !attachments.nodes.is_empty() {
+        let attachments = attachments
+            .nodes
+            .into_iter()
+            .map(|a| match a.subtitle {
+                Some(subtitle) => format!("{} ({subtitle})", style::link(&a.title, &a.url)),
+                None => style::link(&a.title, &a.url),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        table.add_row([Cell::new("attachments"), Cell::new(attachments)]);
+    }
+
     if let Some(description) = description {
         table.add_row([Cell::new("description"), Cell::new(&description)]);
     }
 
-    println!("{table}")
+    println!("{table}");
+
+    if let Some(children) = children.filter(|c| !c.children.is_empty()) {
+        println!();
+        println!("{}", tree::table(&children, full_width));
+    }
 }
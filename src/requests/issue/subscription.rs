@@ -0,0 +1,102 @@
+use crate::client::Client;
+use eyre::{
+    bail,
+    OptionExt as _,
+    Result,
+};
+use graphql_client::GraphQLQuery;
+
+type DateTime = chrono::DateTime<chrono::Utc>;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-subscription.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct IssueSubscribe;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-subscription.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct IssueUnsubscribe;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-subscription.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct IssueReminder;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-subscription.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct IssueSubscribers;
+
+/// Whether a user is subscribed to an issue, the authenticated user if `user_id` is `None`.
+#[builder]
+pub async fn is_subscribed(client: &Client, issue_id: String, user_id: Option<&str>) -> Result<bool> {
+    let query = IssueSubscribers::build_query(issue_subscribers::Variables { id: issue_id });
+    let response = client.req::<_, issue_subscribers::ResponseData>(query).await?;
+    Ok(response.issue.subscribers.nodes.iter().any(|u| match user_id {
+        Some(user_id) => u.id == user_id,
+        None => u.is_me,
+    }))
+}
+
+/// Subscribe a user to an issue, the authenticated user if `user_id` is `None`. Returns the identifier of the issue.
+#[builder]
+pub async fn subscribe(client: &Client, issue_id: String, user_id: Option<String>) -> Result<String> {
+    let query = IssueSubscribe::build_query(issue_subscribe::Variables { id: issue_id, user_id });
+    let response = client
+        .req::<_, issue_subscribe::ResponseData>(query)
+        .await?
+        .issue_subscribe;
+    if !response.success {
+        bail!("could not subscribe to issue");
+    }
+    response.issue.map(|i| i.identifier).ok_or_eyre("no issue")
+}
+
+/// Unsubscribe a user from an issue, the authenticated user if `user_id` is `None`. Returns the identifier of the
+/// issue.
+#[builder]
+pub async fn unsubscribe(client: &Client, issue_id: String, user_id: Option<String>) -> Result<String> {
+    let query = IssueUnsubscribe::build_query(issue_unsubscribe::Variables { id: issue_id, user_id });
+    let response = client
+        .req::<_, issue_unsubscribe::ResponseData>(query)
+        .await?
+        .issue_unsubscribe;
+    if !response.success {
+        bail!("could not unsubscribe from issue");
+    }
+    response.issue.map(|i| i.identifier).ok_or_eyre("no issue")
+}
+
+/// Get a notification about an issue at `at`. Returns the identifier of the issue.
+#[builder]
+pub async fn remind(client: &Client, issue_id: String, at: DateTime) -> Result<String> {
+    if at <= chrono::Utc::now() {
+        bail!("reminder time {at} is in the past");
+    }
+
+    let query = IssueReminder::build_query(issue_reminder::Variables {
+        id: issue_id,
+        reminder_at: at,
+    });
+    let response = client
+        .req::<_, issue_reminder::ResponseData>(query)
+        .await?
+        .issue_reminder;
+    if !response.success {
+        bail!("could not set reminder");
+    }
+    response.issue.map(|i| i.identifier).ok_or_eyre("no issue")
+}
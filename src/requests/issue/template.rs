@@ -0,0 +1,41 @@
+//! Texts derived from an issue for use outside of Linear, e.g. in commit messages and pull requests. The magic word
+//! `Fixes` makes Linear link the commit or pull request to the issue and close it once it is merged.
+
+use super::show::Issue;
+
+/// A conventional commit message: `<type>: <title>`, followed by a `Fixes` trailer. If `type_` is not given it is
+/// derived from the labels of the issue.
+pub fn commit_msg(issue: &Issue, type_: Option<&str>) -> String {
+    let type_ = type_.unwrap_or_else(|| commit_type(issue));
+    format!("{type_}: {}\n\nFixes {}\n", issue.title, issue.identifier)
+}
+
+fn commit_type(issue: &Issue) -> &'static str {
+    let has_label = |names: &[&str]| {
+        issue
+            .labels
+            .nodes
+            .iter()
+            .any(|l| names.iter().any(|n| l.name.eq_ignore_ascii_case(n)))
+    };
+    if has_label(&["bug", "bugfix", "fix"]) {
+        "fix"
+    } else if has_label(&["docs", "documentation"]) {
+        "docs"
+    } else if has_label(&["chore", "maintenance"]) {
+        "chore"
+    } else {
+        "feat"
+    }
+}
+
+/// A markdown pull request description with a link to the issue, its description and a `Fixes` line.
+pub fn pr_body(issue: &Issue) -> String {
+    let mut body = format!("## [{}: {}]({})\n\n", issue.identifier, issue.title, issue.url);
+    if let Some(description) = issue.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        body.push_str(description.trim());
+        body.push_str("\n\n");
+    }
+    body.push_str(&format!("Fixes {}\n", issue.identifier));
+    body
+}
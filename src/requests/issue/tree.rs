@@ -0,0 +1,127 @@
+use crate::{
+    client::Client,
+    style,
+};
+use eyre::Result;
+use graphql_client::GraphQLQuery;
+use serde::Serialize;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-tree.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug, Clone, Serialize, Deserialize"
+)]
+struct IssueTree;
+
+pub type Issue = issue_tree::TreeIssue;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Node {
+    #[serde(flatten)]
+    pub issue: Issue,
+    pub children: Vec<Node>,
+}
+
+/// Fetch an issue and all of its sub-issues. Every issue that has children costs one request.
+#[builder]
+pub async fn request(client: &Client, issue_id: String) -> Result<Node> {
+    fetch(client, issue_id).await
+}
+
+async fn fetch(client: &Client, issue_id: String) -> Result<Node> {
+    let query = IssueTree::build_query(issue_tree::Variables { issue_id });
+    let issue = client.req::<_, issue_tree::ResponseData>(query).await?.issue;
+
+    let mut children = Vec::new();
+    for child in issue.children.nodes {
+        let node = if child.children.nodes.is_empty() {
+            Node {
+                issue: child.tree_issue,
+                children: Vec::new(),
+            }
+        } else {
+            Box::pin(fetch(client, child.tree_issue.id)).await?
+        };
+        children.push(node);
+    }
+
+    Ok(Node {
+        issue: issue.tree_issue,
+        children,
+    })
+}
+
+pub fn table(root: &Node, full_width: bool) -> comfy_table::Table {
+    use comfy_table::*;
+
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::NOTHING);
+    if full_width {
+        table.set_content_arrangement(comfy_table::ContentArrangement::Disabled);
+    } else {
+        table.set_content_arrangement(comfy_table::ContentArrangement::DynamicFullWidth);
+    }
+
+    add_rows(&mut table, root, "", "", "");
+
+    table
+        .column_iter_mut()
+        .next()
+        .unwrap()
+        .set_constraint(ColumnConstraint::ContentWidth);
+
+    table
+}
+
+fn add_rows(table: &mut comfy_table::Table, node: &Node, indent: &str, branch: &str, continuation: &str) {
+    use comfy_table::*;
+
+    let Issue {
+        identifier,
+        title,
+        url,
+        state,
+        assignee,
+        ..
+    } = &node.issue;
+
+    table.add_row([
+        Cell::new(format!("{indent}{branch}{}", style::link(identifier, url))),
+        Cell::new(title),
+        Cell::new(style::fg(&state.name, &state.color)),
+        Cell::new(
+            assignee
+                .as_ref()
+                .map(|a| style::link(&a.display_name, &a.url))
+                .unwrap_or_default(),
+        ),
+    ]);
+
+    let indent = format!("{indent}{continuation}");
+    for (i, child) in node.children.iter().enumerate() {
+        let (branch, continuation) = if i + 1 == node.children.len() {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        add_rows(table, child, &indent, branch, continuation);
+    }
+}
+
+pub fn print(res: Result<Node>, json: bool, full_width: bool) {
+    let res = match res {
+        Ok(res) => res,
+        Err(err) => {
+            eprintln!("{:?}", err);
+            return;
+        }
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&res).unwrap());
+        return;
+    }
+
+    println!("{}", table(&res, full_width))
+}
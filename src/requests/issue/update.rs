@@ -1,50 +1,187 @@
-use crate::client::Client;
-use eyre::Result;
-use graphql_client::GraphQLQuery;
-
-// input: issue_update::IssueUpdateInput {
-//     title,
-//     state_id: None,
-//     description: None,
-//     description_data: None,
-//     assignee_id: None,
-//     parent_id: None,
-//     priority: None,
-//     estimate: None,
-//     subscriber_ids: None,
-//     label_ids: None,
-//     team_id: None,
-//     cycle_id: None,
-//     project_id: None,
-//     project_milestone_id: None,
-//     last_applied_template_id: None,
-//     board_order: None,
-//     sort_order: None,
-//     priority_sort_order: None,
-//     sub_issue_sort_order: None,
-//     due_date: None,
-//     trashed: None,
-//     sla_breaches_at: None,
-//     snoozed_until_at: None,
-//     snoozed_by_id: None,
-// },
+use super::show;
+use crate::{
+    client::Client,
+    dates,
+};
+use eyre::{
+    bail,
+    Result,
+};
+use graphql_client::{
+    GraphQLQuery,
+    QueryBody,
+};
+use serde::{
+    Deserialize,
+    Deserializer,
+    Serialize,
+};
+
+// The query structs are only used for their generated modules, the variables are built by hand, see [`Input`]. These
+// aliases are still needed for the generated (unused) input types.
+#[allow(clippy::upper_case_acronyms)]
+type JSON = serde_json::Value;
+#[allow(clippy::upper_case_acronyms)]
+type UUID = String;
+pub type DateTime = chrono::DateTime<chrono::Utc>;
+type TimelessDate = chrono::NaiveDate;
 
 #[derive(GraphQLQuery)]
 #[graphql(
     query_path = "graphql/issue-update.graphql",
     schema_path = "graphql/linear-api.graphql",
-    input_derives = "Default",
     response_derives = "Debug"
 )]
+#[allow(dead_code)]
 struct IssueUpdate;
 
-#[builder]
-pub async fn request(
-    client: &Client,
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/issue-update.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+#[allow(dead_code)]
+struct IssueBatchUpdate;
+
+pub type Issue = issue_update::IssueUpdateIssueUpdateIssue;
+pub type BatchIssue = issue_batch_update::IssueBatchUpdateIssueBatchUpdateIssues;
+
+/// Maximum number of issues Linear accepts in a single `issueBatchUpdate`.
+pub const BATCH_SIZE: usize = 50;
+
+/// The fields of an `IssueUpdateInput` that `lr` knows how to change. Fields that are `None` are not sent and thus left
+/// untouched, `Some(None)` is sent as `null` and clears the field.
+///
+/// The generated input type can't tell these two apart, which is why the variables of the mutations are built by hand.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Input {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "double_option")]
+    pub assignee_id: Option<Option<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label_ids: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "double_option")]
+    pub cycle_id: Option<Option<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "double_option")]
+    pub project_id: Option<Option<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "double_option")]
+    pub estimate: Option<Option<i64>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "double_option")]
+    pub due_date: Option<Option<TimelessDate>>,
+}
+
+/// Deserialize `null` as `Some(None)` so that cleared fields survive a round trip.
+fn double_option<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Conditions an issue has to meet right before it is updated, so that changes made by someone else in the meantime are
+/// not overwritten. Linear has no conditional updates, the check only narrows the window to the time between fetching
+/// the issue and sending the mutation.
+#[derive(Debug, Clone, Default)]
+pub struct Precondition {
+    /// The issue must not have been updated after this time.
+    pub unmodified_since: Option<DateTime>,
+    /// Name or type of the workflow state the issue must be in.
+    pub state: Option<String>,
+}
+
+impl Precondition {
+    pub fn is_empty(&self) -> bool {
+        self.unmodified_since.is_none() && self.state.is_none()
+    }
+
+    pub fn check(&self, issue: &show::Issue) -> Result<()> {
+        if let Some(since) = self.unmodified_since {
+            if issue.updated_at > since {
+                bail!(
+                    "{} was modified at {}, after {}",
+                    issue.identifier,
+                    dates::fmt(issue.updated_at),
+                    dates::fmt(since)
+                );
+            }
+        }
+        if let Some(state) = &self.state {
+            if !issue.state.name.eq_ignore_ascii_case(state) && !issue.state.type_.eq_ignore_ascii_case(state) {
+                bail!("{} is in state {:?}, expected {state:?}", issue.identifier, issue.state.name);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct UpdateVariables<'a> {
     id: String,
-    title: Option<String>,
-    _state: Option<String>,
-) -> Result<issue_update::IssueUpdateIssueUpdate> {
-    let query = IssueUpdate::build_query(issue_update::Variables { id, title });
-    client.req::<_, issue_update::IssueUpdateIssueUpdate>(query).await
+    input: &'a Input,
+}
+
+#[derive(Serialize)]
+struct BatchUpdateVariables<'a> {
+    ids: Vec<String>,
+    input: &'a Input,
+}
+
+#[builder]
+pub async fn request(client: &Client, id: String, input: &Input) -> Result<Issue> {
+    let query = QueryBody {
+        variables: UpdateVariables { id, input },
+        query: issue_update::QUERY,
+        operation_name: issue_update::OPERATION_NAME,
+    };
+    let response = client
+        .req::<_, issue_update::ResponseData>(query)
+        .await?
+        .issue_update;
+    match response.issue {
+        Some(issue) if response.success => Ok(issue),
+        _ => bail!("could not update issue"),
+    }
+}
+
+/// Apply the same change to up to [`BATCH_SIZE`] issues. `ids` have to be UUIDs, not identifiers. Returns the issues
+/// that were updated.
+#[builder]
+pub async fn batch(client: &Client, ids: Vec<String>, input: &Input) -> Result<Vec<BatchIssue>> {
+    if ids.len() > BATCH_SIZE {
+        bail!("can't update more than {BATCH_SIZE} issues at once");
+    }
+
+    let query = QueryBody {
+        variables: BatchUpdateVariables { ids, input },
+        query: issue_batch_update::QUERY,
+        operation_name: issue_batch_update::OPERATION_NAME,
+    };
+    let response = client
+        .req::<_, issue_batch_update::ResponseData>(query)
+        .await?
+        .issue_batch_update;
+    if !response.success {
+        bail!("could not update issues");
+    }
+
+    Ok(response.issues)
 }
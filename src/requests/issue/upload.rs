@@ -0,0 +1,226 @@
+use super::{
+    attachment,
+    comment,
+    show,
+    update,
+};
+use crate::{
+    client::{
+        self,
+        Client,
+    },
+    shared::UploadTarget,
+};
+use eyre::{
+    bail,
+    Context as _,
+    OptionExt as _,
+    Result,
+};
+use graphql_client::GraphQLQuery;
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/file-upload.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug"
+)]
+struct FileUpload;
+
+#[derive(Debug, Clone)]
+pub struct Uploaded {
+    pub filename: String,
+    pub content_type: String,
+    pub size: usize,
+    pub asset_url: String,
+}
+
+impl Uploaded {
+    fn markdown(&self) -> String {
+        if self.content_type.starts_with("image/") {
+            format!("![{}]({})", self.filename, self.asset_url)
+        } else {
+            format!("[{}]({})", self.filename, self.asset_url)
+        }
+    }
+}
+
+/// How the uploaded files were linked from the issue, to record it in the journal.
+pub enum Linked {
+    Comment(comment::Comment),
+    /// The description was changed from `before` to `after`, which made it the version of the issue `updated_at`.
+    Description {
+        before: String,
+        after: String,
+        updated_at: update::DateTime,
+    },
+    Attachments(Vec<attachment::Attachment>),
+}
+
+pub struct Upload {
+    pub issue_id: String,
+    pub identifier: String,
+    pub files: Vec<Uploaded>,
+    pub linked: Linked,
+}
+
+/// Upload files to Linear's storage and reference them from the issue. `message` is put in front of the links when
+/// posting a comment or appending to the description.
+#[builder]
+pub async fn request(
+    client: &Client,
+    issue_id: String,
+    files: Vec<PathBuf>,
+    target: UploadTarget,
+    message: Option<String>,
+) -> Result<Upload> {
+    let issue = show::request().client(client).issue_id(issue_id).call().await?;
+
+    // Files are only uploaded by a mutation, so a dry run just checks that they can be read.
+    if client.is_dry_run() {
+        for (i, file) in files.iter().enumerate() {
+            let size = std::fs::metadata(file)
+                .with_context(|| format!("could not read {}", file.display()))?
+                .len();
+            let content_type = mime_guess::from_path(file).first_or_octet_stream();
+            eprintln!(
+                "[{}/{}] would upload {} ({}, {content_type})",
+                i + 1,
+                files.len(),
+                file.display(),
+                fmt_size(size as usize)
+            );
+        }
+        return Err(client::DryRun.into());
+    }
+
+    let mut uploaded = Vec::new();
+    for (i, file) in files.iter().enumerate() {
+        eprint!("[{}/{}] uploading {} ... ", i + 1, files.len(), file.display());
+        let result = upload_file(client, file).await;
+        match &result {
+            Ok(u) => eprintln!("{} ({})", fmt_size(u.size), u.content_type),
+            Err(_) => eprintln!("failed"),
+        }
+        uploaded.push(result?);
+    }
+
+    let links = uploaded.iter().map(Uploaded::markdown).collect::<Vec<_>>().join("\n");
+    let text = match message {
+        Some(message) => format!("{message}\n\n{links}"),
+        None => links,
+    };
+
+    let linked = match target {
+        UploadTarget::Comment => {
+            let comment = comment::create()
+                .client(client)
+                .issue_id(issue.id.clone())
+                .body(text)
+                .call()
+                .await?;
+            Linked::Comment(comment)
+        }
+        UploadTarget::Description => {
+            let before = issue.description.clone().unwrap_or_default();
+            let description = match before.trim() {
+                "" => text,
+                _ => format!("{}\n\n{text}", before.trim_end()),
+            };
+            let updated = update::request()
+                .client(client)
+                .id(issue.id.clone())
+                .input(&update::Input {
+                    description: Some(description.clone()),
+                    ..Default::default()
+                })
+                .call()
+                .await?;
+            Linked::Description {
+                before,
+                after: description,
+                updated_at: updated.updated_at,
+            }
+        }
+        UploadTarget::Attachment => {
+            let mut attachments = Vec::new();
+            for u in &uploaded {
+                let attachment = attachment::create()
+                    .client(client)
+                    .issue_id(issue.id.clone())
+                    .url(u.asset_url.clone())
+                    .title(u.filename.clone())
+                    .subtitle(format!("{}, {}", u.content_type, fmt_size(u.size)))
+                    .call()
+                    .await?;
+                attachments.push(attachment);
+            }
+            Linked::Attachments(attachments)
+        }
+    };
+
+    Ok(Upload {
+        issue_id: issue.id,
+        identifier: issue.identifier,
+        files: uploaded,
+        linked,
+    })
+}
+
+async fn upload_file(client: &Client, path: &Path) -> Result<Uploaded> {
+    let filename = path
+        .file_name()
+        .and_then(|f| f.to_str())
+        .ok_or_eyre("invalid file name")?
+        .to_string();
+    let content_type = mime_guess::from_path(path).first_or_octet_stream().to_string();
+    let body = std::fs::read(path).with_context(|| format!("could not read {}", path.display()))?;
+    let size = body.len();
+
+    let query = FileUpload::build_query(file_upload::Variables {
+        filename: filename.clone(),
+        content_type: content_type.clone(),
+        size: size as _,
+    });
+    let response = client.req::<_, file_upload::ResponseData>(query).await?.file_upload;
+    let upload_file = match response.upload_file {
+        Some(upload_file) if response.success => upload_file,
+        _ => bail!("could not request upload url for {filename}"),
+    };
+
+    let headers = upload_file
+        .headers
+        .into_iter()
+        .map(|h| (h.key, h.value))
+        .collect::<Vec<_>>();
+    client
+        .upload(&upload_file.upload_url, &headers, &content_type, body)
+        .await
+        .with_context(|| format!("could not upload {filename}"))?;
+
+    Ok(Uploaded {
+        filename,
+        content_type,
+        size,
+        asset_url: upload_file.asset_url,
+    })
+}
+
+fn fmt_size(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
@@ -0,0 +1,35 @@
+use crate::client::Client;
+use eyre::Result;
+use graphql_client::GraphQLQuery;
+
+pub type DateTime = chrono::DateTime<chrono::Utc>;
+
+pub type Cycle = list_cycles::ListCyclesCyclesNodes;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/cycle_list.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug, Clone, Serialize"
+)]
+struct ListCycles;
+
+/// List the cycles of a team.
+#[builder]
+pub async fn request(client: &Client, team_id: String) -> Result<Vec<Cycle>> {
+    let mut result = Vec::new();
+    let mut after = None;
+    loop {
+        let query = ListCycles::build_query(list_cycles::Variables {
+            team_id: team_id.clone(),
+            after,
+        });
+        let response = client.req::<_, list_cycles::ResponseData>(query).await?.cycles;
+        result.extend(response.nodes);
+        if !response.page_info.has_next_page {
+            break;
+        }
+        after = response.page_info.end_cursor;
+    }
+    Ok(result)
+}
@@ -0,0 +1,29 @@
+use crate::client::Client;
+use eyre::Result;
+use graphql_client::GraphQLQuery;
+
+pub type Label = list_labels::ListLabelsIssueLabelsNodes;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/label_list.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug, Clone, Serialize"
+)]
+struct ListLabels;
+
+#[builder]
+pub async fn request(client: &Client) -> Result<Vec<Label>> {
+    let mut result = Vec::new();
+    let mut after = None;
+    loop {
+        let query = ListLabels::build_query(list_labels::Variables { after });
+        let response = client.req::<_, list_labels::ResponseData>(query).await?.issue_labels;
+        result.extend(response.nodes);
+        if !response.page_info.has_next_page {
+            break;
+        }
+        after = response.page_info.end_cursor;
+    }
+    Ok(result)
+}
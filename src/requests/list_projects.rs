@@ -0,0 +1,29 @@
+use crate::client::Client;
+use eyre::Result;
+use graphql_client::GraphQLQuery;
+
+pub type Project = list_projects::ListProjectsProjectsNodes;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/project_list.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug, Clone, Serialize"
+)]
+struct ListProjects;
+
+#[builder]
+pub async fn request(client: &Client) -> Result<Vec<Project>> {
+    let mut result = Vec::new();
+    let mut after = None;
+    loop {
+        let query = ListProjects::build_query(list_projects::Variables { after });
+        let response = client.req::<_, list_projects::ResponseData>(query).await?.projects;
+        result.extend(response.nodes);
+        if !response.page_info.has_next_page {
+            break;
+        }
+        after = response.page_info.end_cursor;
+    }
+    Ok(result)
+}
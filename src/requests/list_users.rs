@@ -0,0 +1,29 @@
+use crate::client::Client;
+use eyre::Result;
+use graphql_client::GraphQLQuery;
+
+pub type User = list_users::ListUsersUsersNodes;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "graphql/user_list.graphql",
+    schema_path = "graphql/linear-api.graphql",
+    response_derives = "Debug, Clone, Serialize"
+)]
+struct ListUsers;
+
+#[builder]
+pub async fn request(client: &Client) -> Result<Vec<User>> {
+    let mut result = Vec::new();
+    let mut after = None;
+    loop {
+        let query = ListUsers::build_query(list_users::Variables { after });
+        let response = client.req::<_, list_users::ResponseData>(query).await?.users;
+        result.extend(response.nodes);
+        if !response.page_info.has_next_page {
+            break;
+        }
+        after = response.page_info.end_cursor;
+    }
+    Ok(result)
+}
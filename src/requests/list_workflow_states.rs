@@ -16,7 +16,19 @@ struct ListWorkflowStates;
 
 #[builder]
 pub async fn request(client: &Client) -> Result<Vec<WorkflowState>> {
-    let query = ListWorkflowStates::build_query(list_workflow_states::Variables);
-    let response = client.req::<_, list_workflow_states::ResponseData>(query).await?;
-    Ok(response.workflow_states.nodes)
+    let mut result = Vec::new();
+    let mut after = None;
+    loop {
+        let query = ListWorkflowStates::build_query(list_workflow_states::Variables { after });
+        let response = client
+            .req::<_, list_workflow_states::ResponseData>(query)
+            .await?
+            .workflow_states;
+        result.extend(response.nodes);
+        if !response.page_info.has_next_page {
+            break;
+        }
+        after = response.page_info.end_cursor;
+    }
+    Ok(result)
 }
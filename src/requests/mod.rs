@@ -1,4 +1,9 @@
+pub mod board;
 pub mod issue;
+pub mod list_cycles;
+pub mod list_labels;
+pub mod list_projects;
+pub mod list_users;
 pub mod list_workflow_states;
 pub mod me;
 pub mod team;
@@ -14,3 +14,53 @@ pub enum IssueState {
     Completed,
     Canceled,
 }
+
+/// How `issue list` prints issues.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ListFormat {
+    #[default]
+    Table,
+    Json,
+    /// One JSON object per line.
+    Ndjson,
+    /// One identifier per line, e.g. to pipe into other commands.
+    Ids,
+}
+
+/// What `issue list --group-by` groups issues by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum GroupBy {
+    State,
+    Assignee,
+    Project,
+    /// Issues with several labels show up in the group of each label.
+    Label,
+    Priority,
+    Cycle,
+    Team,
+}
+
+/// Where uploaded files end up on the issue.
+#[derive(Clone, Copy, clap::ValueEnum)]
+pub enum UploadTarget {
+    /// Post a new comment linking the files.
+    Comment,
+    /// Append links to the files to the issue description.
+    Description,
+    /// Add each file as an attachment.
+    Attachment,
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum Priority {
+    #[clap(alias = "0")]
+    None = 0,
+    #[clap(alias = "1")]
+    Urgent = 1,
+    #[clap(alias = "2")]
+    High = 2,
+    #[clap(alias = "3")]
+    Medium = 3,
+    #[clap(alias = "4")]
+    Low = 4,
+}
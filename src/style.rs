@@ -0,0 +1,128 @@
+//! Terminal styling. Colors are taken from Linear itself (state and label colors are part of the API responses) and
+//! rendered as truecolor or 256-color escape sequences depending on what the terminal advertises. Identifiers, projects
+//! and users can additionally be rendered as OSC 8 hyperlinks pointing to the Linear app.
+
+use std::{
+    io::IsTerminal as _,
+    sync::OnceLock,
+};
+
+#[derive(Clone, Copy, Default, clap::ValueEnum)]
+pub enum Choice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ColorSupport {
+    None,
+    Ansi256,
+    TrueColor,
+}
+
+static COLOR_SUPPORT: OnceLock<ColorSupport> = OnceLock::new();
+static HYPERLINKS: OnceLock<bool> = OnceLock::new();
+
+/// Decide once whether and how output gets styled. `NO_COLOR` and a non-tty stdout disable styling unless colors are
+/// explicitly requested with `--color always`. Hyperlinks are only emitted in `auto` mode if the terminal is known to
+/// support them.
+pub fn init(color: Choice, hyperlinks: Choice) {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let support = match color {
+        Choice::Never => ColorSupport::None,
+        Choice::Always => detect_color_depth(),
+        Choice::Auto if no_color || !std::io::stdout().is_terminal() => ColorSupport::None,
+        Choice::Auto => detect_color_depth(),
+    };
+    let _ = COLOR_SUPPORT.set(support);
+
+    let hyperlinks = match hyperlinks {
+        Choice::Never => false,
+        Choice::Always => true,
+        Choice::Auto => supports_hyperlinks::on(supports_hyperlinks::Stream::Stdout),
+    };
+    let _ = HYPERLINKS.set(hyperlinks);
+}
+
+fn detect_color_depth() -> ColorSupport {
+    match std::env::var("COLORTERM").as_deref() {
+        Ok("truecolor" | "24bit") => ColorSupport::TrueColor,
+        _ => ColorSupport::Ansi256,
+    }
+}
+
+fn color_support() -> ColorSupport {
+    *COLOR_SUPPORT.get().unwrap_or(&ColorSupport::None)
+}
+
+pub fn enabled() -> bool {
+    color_support() != ColorSupport::None
+}
+
+pub fn hyperlinks_enabled() -> bool {
+    *HYPERLINKS.get().unwrap_or(&false)
+}
+
+fn parse_hex(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    let hex = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect::<String>(),
+        6 => hex.to_string(),
+        _ => return None,
+    };
+    let n = u32::from_str_radix(&hex, 16).ok()?;
+    Some(((n >> 16) as u8, (n >> 8) as u8, n as u8))
+}
+
+/// Map a color onto the 6x6x6 cube of the xterm 256-color palette.
+fn ansi256((r, g, b): (u8, u8, u8)) -> u8 {
+    let scale = |c: u8| ((c as u16 * 5 + 127) / 255) as u8;
+    16 + 36 * scale(r) + 6 * scale(g) + scale(b)
+}
+
+/// Render `text` in the given hex color (e.g. `#eb5757`). Returns the text unchanged if styling is disabled or the
+/// color cannot be parsed.
+pub fn fg(text: impl AsRef<str>, color: &str) -> String {
+    let text = text.as_ref();
+    let Some(rgb) = parse_hex(color) else {
+        return text.to_string();
+    };
+    match color_support() {
+        ColorSupport::None => text.to_string(),
+        ColorSupport::Ansi256 => format!("\x1b[38;5;{}m{text}\x1b[39m", ansi256(rgb)),
+        ColorSupport::TrueColor => format!("\x1b[38;2;{};{};{}m{text}\x1b[39m", rgb.0, rgb.1, rgb.2),
+    }
+}
+
+pub fn bold(text: impl AsRef<str>) -> String {
+    let text = text.as_ref();
+    if enabled() {
+        format!("\x1b[1m{text}\x1b[22m")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Linear does not expose colors for priorities, these match the icons of the web app.
+pub fn priority(priority: f64, label: impl AsRef<str>) -> String {
+    let color = match priority as u8 {
+        1 => "#eb5757",
+        2 => "#f2994a",
+        3 => "#f2c94c",
+        4 => "#95a2b3",
+        _ => return label.as_ref().to_string(),
+    };
+    fg(label, color)
+}
+
+/// Render `text` as an OSC 8 hyperlink to `url` if the terminal supports it.
+pub fn link(text: impl AsRef<str>, url: &str) -> String {
+    let text = text.as_ref();
+    if hyperlinks_enabled() && !text.is_empty() {
+        format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+    } else {
+        text.to_string()
+    }
+}
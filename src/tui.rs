@@ -0,0 +1,613 @@
+//! `lr tui`: browse issues in a full screen terminal UI and change them without switching to the browser. The list is
+//! loaded with the filters of `issue list`, the details of an issue are fetched when it is selected.
+//!
+//! Details are fetched in the background, so that moving through the list doesn't wait for them. Other requests are
+//! awaited right in the event loop. The UI doesn't react while they run, but every key press maps to at most a few
+//! requests.
+
+use crate::{
+    client::Client,
+    editor,
+    journal,
+    requests::{
+        issue::{
+            changes::{
+                priority_label,
+                Snapshot,
+            },
+            comment,
+            list,
+            show,
+            update,
+        },
+        list_users::{
+            self,
+            User,
+        },
+        list_workflow_states::{
+            self,
+            WorkflowState,
+        },
+    },
+    shared::{
+        IssueState,
+        Priority,
+        SortBy,
+    },
+    style,
+};
+use base64::Engine as _;
+use clap::ValueEnum as _;
+use eyre::{
+    bail,
+    Result,
+};
+use ratatui::{
+    crossterm::event::{
+        self,
+        Event,
+        KeyCode,
+        KeyEventKind,
+    },
+    layout::{
+        Constraint,
+        Layout,
+        Rect,
+    },
+    style::{
+        Color,
+        Modifier,
+        Style,
+    },
+    text::{
+        Line,
+        Span,
+        Text,
+    },
+    widgets::{
+        Block,
+        Cell,
+        Clear,
+        List,
+        ListState,
+        Paragraph,
+        Row,
+        Table,
+        TableState,
+        Wrap,
+    },
+    DefaultTerminal,
+    Frame,
+};
+use std::{
+    collections::HashMap,
+    io::Write as _,
+    sync::mpsc,
+    time::Duration,
+};
+
+const HELP: &str =
+    "j/k move  PgUp/PgDn scroll  s state  a assignee  p priority  c comment  o open  b copy branch  r reload  q quit";
+
+/// The filters of `issue list` that the TUI supports.
+pub struct Filter {
+    pub n: Option<usize>,
+    pub sort_by: SortBy,
+    pub assignee: Option<String>,
+    pub state: Option<Vec<IssueState>>,
+    pub parent: Option<String>,
+    pub blocked: bool,
+    pub include_archived: bool,
+}
+
+#[builder]
+pub async fn run(client: &Client, filter: Filter) -> Result<()> {
+    // The mutations would be printed right into the UI.
+    if client.is_dry_run() {
+        bail!("--dry-run is not supported by the TUI");
+    }
+
+    let (details_tx, details_rx) = mpsc::channel();
+    let mut app = App {
+        client,
+        filter,
+        issues: Vec::new(),
+        table: TableState::default(),
+        details: HashMap::new(),
+        loading: HashMap::new(),
+        requests: 0,
+        details_tx,
+        details_rx,
+        states: None,
+        users: None,
+        picker: None,
+        status: String::new(),
+        scroll: 0,
+        quit: false,
+    };
+    // Load the list before switching to the alternate screen, so that errors are printed as usual.
+    app.reload().await?;
+
+    let mut terminal = ratatui::init();
+    let res = app.run(&mut terminal).await;
+    ratatui::restore();
+    res
+}
+
+struct App<'a> {
+    client: &'a Client,
+    filter: Filter,
+    issues: Vec<list::Issue>,
+    table: TableState,
+    /// Details by issue id. Errors are kept too, so that a failing issue isn't fetched over and over again.
+    details: HashMap<String, Result<show::Issue, String>>,
+    /// Issues whose details are being fetched, with the number of the request. Only the response to the latest request
+    /// for an issue is kept, so that details fetched before a change can't replace the ones fetched after it.
+    loading: HashMap<String, u64>,
+    requests: u64,
+    details_tx: mpsc::Sender<Details>,
+    details_rx: mpsc::Receiver<Details>,
+    states: Option<Vec<WorkflowState>>,
+    users: Option<Vec<User>>,
+    picker: Option<Picker>,
+    /// Shown in the footer instead of the help until the next key press.
+    status: String,
+    scroll: u16,
+    quit: bool,
+}
+
+/// The details of an issue fetched in the background: its id, the number of the request and the result.
+type Details = (String, u64, Result<show::Issue, String>);
+
+/// A popup to pick a new value for a field of the selected issue.
+struct Picker {
+    title: &'static str,
+    /// The update that sets the value, and its label.
+    options: Vec<(update::Input, String)>,
+    state: ListState,
+}
+
+impl App<'_> {
+    async fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        while !self.quit {
+            while let Ok((id, request, res)) = self.details_rx.try_recv() {
+                if self.loading.get(&id) == Some(&request) {
+                    self.loading.remove(&id);
+                    self.details.insert(id, res);
+                }
+            }
+            if let Some(issue) = self.selected() {
+                if !self.details.contains_key(&issue.id) && !self.loading.contains_key(&issue.id) {
+                    let id = issue.id.clone();
+                    self.load_details(id);
+                }
+            }
+
+            terminal.draw(|frame| self.draw(frame))?;
+
+            // Don't block on input while details are on their way, so that they are drawn when they arrive.
+            if !self.loading.is_empty() && !event::poll(Duration::from_millis(50))? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.status.clear();
+                    self.on_key(key.code, terminal).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn reload(&mut self) -> Result<()> {
+        let Filter {
+            n,
+            sort_by,
+            assignee,
+            state,
+            parent,
+            blocked,
+            include_archived,
+        } = &self.filter;
+        self.issues = list::request()
+            .client(self.client)
+            .maybe_n(*n)
+            .sort_by(sort_by.clone())
+            .maybe_assignee(assignee.clone())
+            .maybe_state(state.clone())
+            .maybe_parent(parent.clone())
+            .blocked(*blocked)
+            .include_archived(*include_archived)
+            .call()
+            .await?;
+        self.details.clear();
+        self.loading.clear();
+        self.scroll = 0;
+        let selected = self.table.selected().unwrap_or(0).min(self.issues.len().saturating_sub(1));
+        self.table.select((!self.issues.is_empty()).then_some(selected));
+        Ok(())
+    }
+
+    /// Fetch the details of an issue in the background. Details that were fetched before stay until they arrive.
+    fn load_details(&mut self, id: String) {
+        self.requests += 1;
+        self.loading.insert(id.clone(), self.requests);
+
+        let (client, tx, request) = (self.client.clone(), self.details_tx.clone(), self.requests);
+        tokio::spawn(async move {
+            let res = show::request()
+                .client(&client)
+                .issue_id(id.clone())
+                .call()
+                .await
+                .map_err(|err| format!("{err:#}"));
+            // The receiver is gone once the TUI was closed.
+            let _ = tx.send((id, request, res));
+        });
+    }
+
+    fn selected(&self) -> Option<&list::Issue> {
+        self.table.selected().and_then(|i| self.issues.get(i))
+    }
+
+    fn selected_details(&self) -> Option<&show::Issue> {
+        self.selected()
+            .and_then(|issue| self.details.get(&issue.id))
+            .and_then(|details| details.as_ref().ok())
+    }
+
+    async fn on_key(&mut self, code: KeyCode, terminal: &mut DefaultTerminal) -> Result<()> {
+        if let Some(picker) = &mut self.picker {
+            match code {
+                KeyCode::Esc | KeyCode::Char('q') => self.picker = None,
+                KeyCode::Down | KeyCode::Char('j') => picker.state.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => picker.state.select_previous(),
+                KeyCode::Enter => {
+                    let input = picker.state.selected().and_then(|i| picker.options.get(i)).map(|(i, _)| i.clone());
+                    self.picker = None;
+                    if let Some(input) = input {
+                        self.apply(input).await;
+                    }
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => self.quit = true,
+            KeyCode::Down | KeyCode::Char('j') => self.select(|i| i + 1),
+            KeyCode::Up | KeyCode::Char('k') => self.select(|i| i.saturating_sub(1)),
+            KeyCode::Home | KeyCode::Char('g') => self.select(|_| 0),
+            KeyCode::End | KeyCode::Char('G') => self.select(|_| usize::MAX),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::Char('s') => self.pick_state().await,
+            KeyCode::Char('a') => self.pick_assignee().await,
+            KeyCode::Char('p') => self.pick_priority(),
+            KeyCode::Char('c') => self.comment(terminal).await,
+            KeyCode::Char('o') => {
+                if let Some(issue) = self.selected() {
+                    if let Err(err) = open::that_detached(&issue.url) {
+                        self.status = format!("could not open {}: {err}", issue.url);
+                    }
+                }
+            }
+            KeyCode::Char('b') => {
+                if let Some(issue) = self.selected_details() {
+                    let branch = issue.branch_name.clone();
+                    self.status = match copy(&branch) {
+                        Ok(()) => format!("copied {branch}"),
+                        Err(err) => format!("could not copy {branch}: {err}"),
+                    };
+                }
+            }
+            KeyCode::Char('r') => {
+                if let Err(err) = self.reload().await {
+                    self.status = format!("{err:#}");
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn select(&mut self, f: impl FnOnce(usize) -> usize) {
+        if self.issues.is_empty() {
+            return;
+        }
+        let selected = f(self.table.selected().unwrap_or(0)).min(self.issues.len() - 1);
+        if self.table.selected() != Some(selected) {
+            self.table.select(Some(selected));
+            self.scroll = 0;
+        }
+    }
+
+    fn open_picker(&mut self, title: &'static str, options: Vec<(update::Input, String)>, current: Option<usize>) {
+        let mut state = ListState::default();
+        state.select(Some(current.unwrap_or(0)));
+        self.picker = Some(Picker { title, options, state });
+    }
+
+    async fn pick_state(&mut self) {
+        if self.states.is_none() {
+            match list_workflow_states::request().client(self.client).call().await {
+                Ok(states) => self.states = Some(states),
+                Err(err) => return self.status = format!("{err:#}"),
+            }
+        }
+        let Some(issue) = self.selected_details() else {
+            return;
+        };
+
+        let mut states = self
+            .states
+            .iter()
+            .flatten()
+            .filter(|s| s.team.id == issue.team.id && s.archived_at.is_none())
+            .collect::<Vec<_>>();
+        states.sort_by(|a, b| a.position.total_cmp(&b.position));
+        let current = states.iter().position(|s| s.id == issue.state.id);
+        let options = states
+            .iter()
+            .map(|s| {
+                let input = update::Input {
+                    state_id: Some(s.id.clone()),
+                    ..Default::default()
+                };
+                (input, s.name.clone())
+            })
+            .collect();
+        self.open_picker("State", options, current);
+    }
+
+    async fn pick_assignee(&mut self) {
+        if self.users.is_none() {
+            match list_users::request().client(self.client).call().await {
+                Ok(users) => self.users = Some(users),
+                Err(err) => return self.status = format!("{err:#}"),
+            }
+        }
+        let Some(issue) = self.selected_details() else {
+            return;
+        };
+
+        let users = self.users.iter().flatten().filter(|u| u.active).collect::<Vec<_>>();
+        let unassign = update::Input {
+            assignee_id: Some(None),
+            ..Default::default()
+        };
+        let mut options = vec![(unassign, "none".to_string())];
+        options.extend(users.iter().map(|u| {
+            let input = update::Input {
+                assignee_id: Some(Some(u.id.clone())),
+                ..Default::default()
+            };
+            (input, u.display_name.clone())
+        }));
+        let current = match &issue.assignee {
+            Some(assignee) => users.iter().position(|u| u.id == assignee.id).map(|i| i + 1),
+            None => Some(0),
+        };
+        self.open_picker("Assignee", options, current);
+    }
+
+    fn pick_priority(&mut self) {
+        let Some(issue) = self.selected_details() else {
+            return;
+        };
+
+        let priorities = Priority::value_variants();
+        let current = priorities.iter().position(|p| *p as i64 == issue.priority as i64);
+        let options = priorities
+            .iter()
+            .map(|p| {
+                let input = update::Input {
+                    priority: Some(*p as i64),
+                    ..Default::default()
+                };
+                (input, priority_label(*p as i64).to_string())
+            })
+            .collect();
+        self.open_picker("Priority", options, current);
+    }
+
+    /// Update the selected issue and record the change in the journal, like `issue update` does.
+    async fn apply(&mut self, input: update::Input) {
+        let Some(issue) = self.selected_details() else {
+            return;
+        };
+        let (id, identifier) = (issue.id.clone(), issue.identifier.clone());
+        let before = Snapshot::from(issue).revert(&input);
+
+        match update::request().client(self.client).id(id.clone()).input(&input).call().await {
+            Ok(updated) => {
+                journal::record(journal::Change::Update {
+                    issues: vec![journal::IssueChange {
+                        id: id.clone(),
+                        identifier: identifier.clone(),
+                        before,
+                        after: input,
+                        updated_at: Some(updated.updated_at),
+                    }],
+                });
+                self.status = format!("{identifier} updated");
+                self.load_details(id);
+            }
+            Err(err) => self.status = format!("{err:#}"),
+        }
+    }
+
+    /// Write a comment on the selected issue in `$EDITOR`.
+    async fn comment(&mut self, terminal: &mut DefaultTerminal) {
+        let Some(issue) = self.selected() else {
+            return;
+        };
+        let (id, identifier) = (issue.id.clone(), issue.identifier.clone());
+
+        // The editor needs the terminal for itself.
+        ratatui::restore();
+        let body = editor::edit(&format!("{identifier}-comment.md"), "");
+        *terminal = ratatui::init();
+
+        self.status = match body {
+            Ok(body) if body.trim().is_empty() => "empty comment, not sent".to_string(),
+            Ok(body) => match comment::create().client(self.client).issue_id(id).body(body).call().await {
+                Ok(comment) => {
+                    journal::record(journal::Change::Comment {
+                        identifier: identifier.clone(),
+                        comment_id: comment.id,
+                    });
+                    format!("commented on {identifier}")
+                }
+                Err(err) => format!("{err:#}"),
+            },
+            Err(err) => format!("{err:#}"),
+        };
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, footer] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [list_area, details_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(main);
+
+        self.draw_list(frame, list_area);
+        self.draw_details(frame, details_area);
+        let footer_text = if self.status.is_empty() { HELP } else { self.status.as_str() };
+        frame.render_widget(Paragraph::new(footer_text).style(Style::new().add_modifier(Modifier::DIM)), footer);
+
+        if let Some(picker) = &mut self.picker {
+            let height = (picker.options.len() as u16 + 2).min(main.height);
+            let area = centered(main, 40, height);
+            let list = List::new(picker.options.iter().map(|(_, label)| label.as_str()))
+                .block(Block::bordered().title(picker.title))
+                .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(list, area, &mut picker.state);
+        }
+    }
+
+    fn draw_list(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = self.issues.iter().map(|issue| {
+            // Prefer the details, they are fetched again after the issue was changed.
+            let (state, color, assignee) = match self.details.get(&issue.id) {
+                Some(Ok(details)) => (
+                    &details.state.name,
+                    &details.state.color,
+                    details.assignee.as_ref().map(|a| a.display_name.as_str()),
+                ),
+                _ => (
+                    &issue.state.name,
+                    &issue.state.color,
+                    issue.assignee.as_ref().map(|a| a.display_name.as_str()),
+                ),
+            };
+            Row::new([
+                Cell::from(issue.identifier.clone()),
+                Cell::from(Span::styled(state.clone(), fg(color))),
+                Cell::from(assignee.unwrap_or_default().to_string()),
+                Cell::from(issue.title.clone()),
+            ])
+        });
+
+        let widths = [
+            Constraint::Length(10),
+            Constraint::Length(14),
+            Constraint::Length(14),
+            Constraint::Fill(1),
+        ];
+        let table = Table::new(rows, widths)
+            .block(Block::bordered().title(format!("Issues ({})", self.issues.len())))
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, area, &mut self.table);
+    }
+
+    fn draw_details(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered();
+        let text = match self.selected().map(|issue| (issue, self.details.get(&issue.id))) {
+            None => Text::from("no issues"),
+            Some((_, None)) => Text::from("loading…"),
+            Some((_, Some(Err(err)))) => Text::from(err.as_str()),
+            Some((_, Some(Ok(issue)))) => details(issue),
+        };
+        let paragraph = Paragraph::new(text)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(paragraph, area);
+    }
+}
+
+fn details(issue: &show::Issue) -> Text<'static> {
+    let field = |name: &str, value: Vec<Span<'static>>| {
+        let mut spans = vec![Span::styled(format!("{name:<10}"), Style::new().add_modifier(Modifier::DIM))];
+        spans.extend(value);
+        Line::from(spans)
+    };
+    let plain = |value: Option<String>| vec![Span::raw(value.unwrap_or_else(|| "-".to_string()))];
+
+    let labels = issue
+        .labels
+        .nodes
+        .iter()
+        .flat_map(|l| [Span::styled(l.name.clone(), fg(&l.color)), Span::raw(" ")])
+        .collect();
+
+    let mut lines = vec![
+        Line::styled(
+            format!("{} {}", issue.identifier, issue.title),
+            Style::new().add_modifier(Modifier::BOLD),
+        ),
+        Line::default(),
+        field("state", vec![Span::styled(issue.state.name.clone(), fg(&issue.state.color))]),
+        field("assignee", plain(issue.assignee.as_ref().map(|a| a.display_name.clone()))),
+        field("priority", plain(Some(issue.priority_label.clone()))),
+        field("labels", labels),
+        field("project", plain(issue.project.as_ref().map(|p| p.name.clone()))),
+        field("estimate", plain(issue.estimate.map(|e| e.to_string()))),
+        field("due date", plain(issue.due_date.map(|d| d.to_string()))),
+        field("branch", plain(Some(issue.branch_name.clone()))),
+        field("url", plain(Some(issue.url.clone()))),
+        Line::default(),
+    ];
+    lines.extend(
+        issue
+            .description
+            .as_deref()
+            .unwrap_or_default()
+            .lines()
+            .map(|line| Line::raw(line.to_string())),
+    );
+    Text::from(lines)
+}
+
+/// Linear colors are hex strings like `#f2c94c`.
+fn fg(color: &str) -> Style {
+    let hex = color.trim_start_matches('#');
+    if !style::enabled() || hex.len() != 6 {
+        return Style::new();
+    }
+    match u32::from_str_radix(hex, 16) {
+        Ok(rgb) => Style::new().fg(Color::from_u32(rgb)),
+        Err(_) => Style::new(),
+    }
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Copy `text` to the clipboard with the OSC 52 escape sequence, which works over ssh as well. Terminals that don't
+/// support it ignore the sequence.
+fn copy(text: &str) -> std::io::Result<()> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{encoded}\x07")?;
+    stdout.flush()
+}